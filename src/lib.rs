@@ -0,0 +1,21 @@
+pub mod parser;
+pub mod runtime;
+
+pub use num_complex::Complex64;
+pub use runtime::{ErrorKind, Limits, Runtime, RuntimeError, RuntimeState, Value};
+
+const PRELUDE: &str = include_str!("lbstandard.lbz");
+
+/// Parses and runs Leibniz source code on top of the standard library, with only the builtin
+/// functions Leibniz itself provides. Errors are given back as the message shown to the user.
+///
+/// ```
+/// let value = leibniz::evaluate_str("let f(x) = x^2\nf(3)").unwrap();
+/// assert_eq!(value, leibniz::Value::real(9.0));
+///
+/// let error = leibniz::evaluate_str("1 + nothing").unwrap_err();
+/// assert_eq!(error, "unknown variable: nothing at line 1, column 5");
+/// ```
+pub fn evaluate_str(source: &str) -> Result<Value, String> {
+    Runtime::new().run(source)
+}
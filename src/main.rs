@@ -1,19 +1,4 @@
-use std::{fs, time};
-
-use glium::{
-    glutin::{self, event::WindowEvent},
-    Display, Surface,
-};
-use glutin::{
-    event::Event, event_loop::ControlFlow, event_loop::EventLoop, window::WindowBuilder,
-    ContextBuilder,
-};
-use time::{Duration, Instant};
-
-mod parser;
-mod runtime;
-
-const PRELUDE: &'static str = include_str!("lbstandard.lbz");
+use std::fs;
 
 fn main() {
     let args = std::env::args().collect::<Vec<String>>();
@@ -22,27 +7,9 @@ fn main() {
         error("leibniz: no input file found");
     }
 
-    let mut parsed_total = match parser::parse_leibniz_file(PRELUDE) {
-        Ok(ast) => ast,
-        Err(err) => {
-            error(&err);
-            unreachable!()
-        }
-    };
-
     let filename = &args[1];
     let file = fs::read_to_string(filename).expect("something went wrong reading the file");
 
-    let parsed_file = match parser::parse_leibniz_file(&file) {
-        Ok(ast) => ast,
-        Err(err) => {
-            error(&err);
-            unreachable!()
-        }
-    };
-
-    parsed_total.append_tree(parsed_file);
-
     /*let event_loop = EventLoop::new();
     let window_builder = WindowBuilder::new();
     let context_builder = ContextBuilder::new();
@@ -69,7 +36,7 @@ fn main() {
         }
     });*/
 
-    match runtime::execute(parsed_total) {
+    match leibniz::evaluate_str(&file) {
         Ok(value) => println!("{}", value),
         Err(err) => println!("{}", err),
     }
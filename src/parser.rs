@@ -1,4 +1,4 @@
-use error::LineColLocation;
+use error::{InputLocation, LineColLocation};
 use pest::{
     error::Error,
     error::ErrorVariant,
@@ -7,7 +7,6 @@ use pest::{
     Parser,
 };
 use pest_derive::Parser;
-use crate::runtime::Value;
 
 #[derive(Parser)]
 #[grammar = "leibniz.pest"]
@@ -22,36 +21,76 @@ pub enum Operator {
     Power,
     Modulo,
     Equals,
+    NotEquals,
     GreaterThan,
     LessThan,
     GreaterThanOrEquals,
     LessThanOrEquals,
+    And, // only evaluates the right hand side when the left hand side is true
+    Or,  // only evaluates the right hand side when the left hand side is false
+}
+
+// how a loop combines the results of its body
+#[derive(Debug)]
+pub enum LoopMode {
+    Sum,     // adds them up, which is what a loop does unless told otherwise
+    Product, // multiplies them together
+    Last,    // keeps only the last one
+    Collect, // puts them all in an array
 }
 
 type InnerNode<'a> = Box<ParserNode<'a>>;
 
+// the line and column a node starts at in the source, used to point runtime errors at their cause
+pub type Span = (usize, usize);
+
 // represents a node in the AST which the parser can construct.
 // leibniz's grammar is defined in leibniz.pest, from which the parser is generated from.
 // this enum acts as a mapping between that grammar and rust code
 #[derive(Debug)]
 pub enum ParserNode<'a> {
     Number(f64, bool),   // any number, either real or imaginary
-    Identifier(&'a str), // any identifier, such as a variable name, function name, etc
-    Operation(InnerNode<'a>, Operator, InnerNode<'a>), // an arithmetic operation with a left and right hand side
-    Assignment(Vec<&'a str>, InnerNode<'a>), // re-assigning (possibly multiple) variables to a value
-    FunctionCall(&'a str, Vec<ParserNode<'a>>), // a function call with an array of expressions as arguments
+    Identifier(&'a str, Span), // any identifier, such as a variable name, function name, etc
+    Str(&'a str),        // a string literal, without its surrounding quotes
+    Operation(InnerNode<'a>, Operator, InnerNode<'a>, Span), // an arithmetic operation with a left and right hand side
+    Assignment(Vec<&'a str>, InnerNode<'a>, Span), // re-assigning (possibly multiple) variables to a value
+    GlobalAssignment(&'a str, InnerNode<'a>, Span), // re-assigning a global variable, even from within a function
+    DestructuringAssignment(Vec<&'a str>, InnerNode<'a>, Span), // re-assigning variables to the elements of an array, by position
+    FunctionCall(&'a str, Vec<ParserNode<'a>>, Span), // a function call with an array of expressions as arguments
     Conditional(InnerNode<'a>, InnerNode<'a>, InnerNode<'a>), // a conditional with a predicate, true expression and false expression
-    FunctionDeclaration(&'a str, Vec<&'a str>, InnerNode<'a>),
-    VariableDeclaration(&'a str, InnerNode<'a>),
+    FunctionDeclaration(&'a str, Vec<&'a str>, InnerNode<'a>, Span),
+    Lambda(&'a str, Vec<&'a str>, InnerNode<'a>), // an anonymous function, named after its own source
+    VariableDeclaration(&'a str, InnerNode<'a>, Span),
     Range(InnerNode<'a>, InnerNode<'a>, InnerNode<'a>), // any range with a lower bound, upper bound and a step
     Array(Vec<ParserNode<'a>>), // an array full of expressions
-    Index(InnerNode<'a>, InnerNode<'a>),
-    Loop(&'a str, InnerNode<'a>, InnerNode<'a>), // a loop construct that works on ranges and a named parameter
+    Index(InnerNode<'a>, InnerNode<'a>, Span),
+    Loop(&'a str, LoopMode, InnerNode<'a>, InnerNode<'a>, Span), // a loop construct that works on ranges or arrays and a named parameter
+    While(InnerNode<'a>, InnerNode<'a>, Span),   // a loop that repeats its body for as long as the predicate holds
+    Negate(InnerNode<'a>, Span),                 // the negation of an expression, like -x
     Factorial(InnerNode<'a>),                    // factorial of an expression
     Tree(Vec<ParserNode<'a>>),                   // a tree of nodes
 }
 
 impl<'a> ParserNode<'a> {
+    // where the node is in the source, for the nodes that keep track of it
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ParserNode::Identifier(_, span)
+            | ParserNode::Operation(_, _, _, span)
+            | ParserNode::FunctionCall(_, _, span)
+            | ParserNode::Index(_, _, span)
+            | ParserNode::Negate(_, span)
+            | ParserNode::Assignment(_, _, span)
+            | ParserNode::DestructuringAssignment(_, _, span)
+            | ParserNode::GlobalAssignment(_, _, span)
+            | ParserNode::VariableDeclaration(_, _, span)
+            | ParserNode::FunctionDeclaration(_, _, _, span)
+            | ParserNode::Loop(_, _, _, _, span)
+            | ParserNode::While(_, _, span) => Some(*span),
+            _ => None,
+        }
+    }
+
     pub fn append_tree(&mut self, new_nodes: ParserNode<'a>) {
         if let ParserNode::Tree(nodes) = self {
             if let ParserNode::Tree(mut new_nodes) = new_nodes {
@@ -63,7 +102,7 @@ impl<'a> ParserNode<'a> {
     }
 }
 
-pub fn parse_leibniz_file(file: &str) -> Result<ParserNode, String> {
+pub fn parse_leibniz_file(file: &str) -> Result<ParserNode<'_>, String> {
     let root = match LeibnizParser::parse(Rule::file, file) {
         Ok(mut pair) => pair.next().unwrap(),
         Err(pair) => {
@@ -79,6 +118,9 @@ pub fn parse_leibniz_file(file: &str) -> Result<ParserNode, String> {
             Rule::var_decl => parse_var_decl(pair),
             Rule::tree | Rule::expression => parse_tree_or_expression(pair),
             Rule::assignment => parse_assignment(pair),
+            Rule::compound_assignment => parse_compound_assignment(pair),
+            Rule::global_assignment => parse_global_assignment(pair),
+            Rule::destructuring_assignment => parse_destructuring_assignment(pair),
             Rule::rloop => parse_loop(pair),
             Rule::EOI => break,
             _ => unreachable!(),
@@ -94,10 +136,13 @@ fn parse_value(value: Pair<Rule>) -> ParserNode {
     let mut left_val = match pairs[0].as_rule() {
         Rule::number => parse_number(pairs[0].clone()),
         Rule::identifier => parse_identifier(pairs[0].clone()),
+        Rule::string => parse_string(pairs[0].clone()),
         Rule::expression => parse_expression(pairs[0].clone()), // parenthesis
         Rule::func_call => parse_func_call(pairs[0].clone()),
         Rule::array => parse_array(pairs[0].clone()),
         Rule::rloop => parse_loop(pairs[0].clone()),
+        Rule::wloop => parse_while(pairs[0].clone()),
+        Rule::lambda => parse_lambda(pairs[0].clone()),
         _ => {
             println!("Unknown parser pair: {:#?}", pairs);
             unreachable!()
@@ -110,12 +155,10 @@ fn parse_value(value: Pair<Rule>) -> ParserNode {
 
     let mut dropoff = 1;
 
-    for i in 1..pairs.len() {
-        let pair = &pairs[i];
-
+    for pair in pairs.iter().skip(1) {
         if pair.as_rule() == Rule::index {
             let index_pairs = pairs_to_vec(pair.clone());
-            left_val = ParserNode::Index(Box::new(left_val), Box::new(parse_expression(index_pairs[1].clone())));
+            left_val = ParserNode::Index(Box::new(left_val), Box::new(parse_expression(index_pairs[1].clone())), span_of(pair));
             dropoff += 1;
         } else {
             break;
@@ -126,9 +169,7 @@ fn parse_value(value: Pair<Rule>) -> ParserNode {
         return left_val;
     }
 
-    for i in dropoff..pairs.len() {
-        let pair = &pairs[i];
-
+    for pair in pairs.iter().skip(dropoff) {
         if pair.as_rule() == Rule::fact {
             left_val = ParserNode::Factorial(Box::new(left_val));
             dropoff += 1;
@@ -141,8 +182,12 @@ fn parse_value(value: Pair<Rule>) -> ParserNode {
         return left_val;
     }
 
-    let right_val = parse_value(pairs[dropoff + 1].clone());
-    ParserNode::Operation(Box::new(left_val), Operator::Power, Box::new(right_val))
+    let right_val = parse_operand(pairs[dropoff + 1].clone());
+    ParserNode::Operation(Box::new(left_val), Operator::Power, Box::new(right_val), span_of(&pairs[dropoff]))
+}
+
+fn parse_string(string: Pair<Rule>) -> ParserNode {
+    ParserNode::Str(string.into_inner().next().unwrap().as_str())
 }
 
 fn parse_number(number: Pair<Rule>) -> ParserNode {
@@ -159,25 +204,32 @@ fn parse_number(number: Pair<Rule>) -> ParserNode {
         num
     };
 
-    ParserNode::Number(num_str.parse().unwrap(), imaginary)
+    let magnitude = if let Some(hex) = num_str.strip_prefix("0x") {
+        parse_integer(hex, 16)
+    } else if let Some(binary) = num_str.strip_prefix("0b") {
+        parse_integer(binary, 2)
+    } else {
+        num_str.replace('_', "").parse().unwrap()
+    };
+
+    ParserNode::Number(magnitude, imaginary)
+}
+
+// digits in the given radix, with any _ separators skipped.
+// added up as a float, so integers too big for i64 don't overflow
+fn parse_integer(digits: &str, radix: u32) -> f64 {
+    digits
+        .chars()
+        .filter(|c| *c != '_')
+        .fold(0.0, |n, c| n * radix as f64 + c.to_digit(radix).unwrap() as f64)
 }
 
 fn parse_identifier(identifier: Pair<Rule>) -> ParserNode {
-    ParserNode::Identifier(identifier.as_str())
+    ParserNode::Identifier(identifier.as_str(), span_of(&identifier))
 }
 
 fn parse_term(term: Pair<Rule>) -> ParserNode {
-    let pairs = pairs_to_vec(term);
-    let left_term = parse_value(pairs[0].clone());
-
-    if pairs.len() == 1 {
-        return left_term;
-    }
-
-    let operator = parse_operator(pairs[1].clone());
-    let right_term = parse_term(pairs[2].clone());
-
-    ParserNode::Operation(Box::new(left_term), operator, Box::new(right_term))
+    parse_chain(pairs_to_vec(term), parse_operand)
 }
 
 fn parse_array(array: Pair<Rule>) -> ParserNode {
@@ -192,47 +244,57 @@ fn parse_array(array: Pair<Rule>) -> ParserNode {
 }
 
 fn parse_expression(expression: Pair<Rule>) -> ParserNode {
-    let pairs = pairs_to_vec(expression);
+    let mut pairs = pairs_to_vec(expression);
+    let conditional = if pairs.last().unwrap().as_rule() == Rule::conditional {
+        pairs.pop()
+    } else {
+        None
+    };
 
-    if pairs[0].as_rule() == Rule::negation_expression {
-        return parse_negation_expression(pairs[0].clone());
-    }
+    let operation = parse_chain(pairs, parse_comparison);
 
-    if pairs.len() == 1 {
-        return parse_term(pairs[0].clone());
+    match conditional {
+        Some(conditional) => parse_conditional(conditional, operation),
+        None => operation,
     }
+}
 
-    let mut operation = parse_term(pairs[0].clone());
-    let conditional_offset = if pairs.last().unwrap().as_rule() == Rule::conditional {
-        1
-    } else {
-        0
-    };
+fn parse_comparison(comparison: Pair<Rule>) -> ParserNode {
+    parse_chain(pairs_to_vec(comparison), parse_sum)
+}
 
-    for i in (0..pairs.len() - 1 - conditional_offset).step_by(2) {
-        let operator = parse_operator(pairs[i + 1].clone());
-        let right = parse_term(pairs[i + 2].clone());
-        operation = ParserNode::Operation(Box::new(operation), operator, Box::new(right));
-    }
+fn parse_sum(sum: Pair<Rule>) -> ParserNode {
+    parse_chain(pairs_to_vec(sum), parse_term)
+}
 
-    if conditional_offset > 0 {
-        return parse_conditional(pairs[pairs.len() - 1].clone(), operation);
+// folds operands separated by operators of the same precedence from the left, so 8 / 2 / 2 is 2
+fn parse_chain(pairs: Vec<Pair<Rule>>, parse_operand: fn(Pair<Rule>) -> ParserNode) -> ParserNode {
+    let mut operation = parse_operand(pairs[0].clone());
+
+    for i in (0..pairs.len() - 1).step_by(2) {
+        let operator = parse_operator(pairs[i + 1].clone());
+        let right = parse_operand(pairs[i + 2].clone());
+        operation = ParserNode::Operation(Box::new(operation), operator, Box::new(right), span_of(&pairs[i + 1]));
     }
 
     operation
 }
 
-fn parse_negation_expression(expression: Pair<Rule>) -> ParserNode {
-    let pairs = pairs_to_vec(expression);
-
-    ParserNode::Operation(
-        Box::new(parse_expression(pairs[0].clone())),
-        Operator::Multiply,
-        Box::new(ParserNode::Number(-1.0, false)),
-    )
+// a minus sign only applies to what directly follows it, so -x + 1 is (-x) + 1. powers bind tighter
+// than it, even on number literals, so -2^2 is -(2^2)
+fn parse_operand(operand: Pair<Rule>) -> ParserNode {
+    match operand.as_rule() {
+        Rule::negation => {
+            let span = span_of(&operand);
+            let inner = operand.into_inner().next().unwrap();
+            ParserNode::Negate(Box::new(parse_operand(inner)), span)
+        }
+        _ => parse_value(operand),
+    }
 }
 
 fn parse_assignment(assignment: Pair<Rule>) -> ParserNode {
+    let span = span_of(&assignment);
     let pairs = pairs_to_vec(assignment);
 
     let identifiers = pairs
@@ -243,19 +305,66 @@ fn parse_assignment(assignment: Pair<Rule>) -> ParserNode {
 
     let expression = parse_expression(pairs.last().unwrap().clone());
 
-    ParserNode::Assignment(identifiers, Box::new(expression))
+    ParserNode::Assignment(identifiers, Box::new(expression), span)
+}
+
+// x += y is the same as x = x + y, and likewise for the other operators
+fn parse_compound_assignment(assignment: Pair<Rule>) -> ParserNode {
+    let span = span_of(&assignment);
+    let pairs = pairs_to_vec(assignment);
+
+    let identifier = pairs[0].as_str();
+    let operator = pairs[1].clone().into_inner().next().unwrap();
+    let expression = parse_tree_or_expression(pairs[2].clone());
+
+    let operation = ParserNode::Operation(
+        Box::new(parse_identifier(pairs[0].clone())),
+        parse_operator(operator),
+        Box::new(expression),
+        span_of(&pairs[1]),
+    );
+
+    ParserNode::Assignment(vec![identifier], Box::new(operation), span)
+}
+
+fn parse_destructuring_assignment(assignment: Pair<Rule>) -> ParserNode {
+    let span = span_of(&assignment);
+    let pairs = pairs_to_vec(assignment);
+
+    let identifiers = pairs
+        .iter()
+        .filter(|pair| pair.as_rule() == Rule::identifier)
+        .map(|pair| pair.as_str())
+        .collect();
+
+    let expression = parse_tree_or_expression(pairs.last().unwrap().clone());
+
+    ParserNode::DestructuringAssignment(identifiers, Box::new(expression), span)
+}
+
+fn parse_global_assignment(assignment: Pair<Rule>) -> ParserNode {
+    let span = span_of(&assignment);
+    let pairs = pairs_to_vec(assignment);
+
+    ParserNode::GlobalAssignment(
+        pairs[1].as_str(),
+        Box::new(parse_tree_or_expression(pairs[3].clone())),
+        span,
+    )
 }
 
 fn parse_var_decl(declaration: Pair<Rule>) -> ParserNode {
+    let span = span_of(&declaration);
     let pairs = pairs_to_vec(declaration);
 
     ParserNode::VariableDeclaration(
         pairs[0].as_str(),
         Box::new(parse_tree_or_expression(pairs[2].clone())),
+        span,
     )
 }
 
-fn parse_param_list(param_list: Pair<Rule>) -> Vec<&str> {
+fn parse_param_list(param_list: Pair<'_, Rule>) -> Vec<&str> {
     param_list
         .into_inner()
         .filter(|pair| pair.as_rule() == Rule::identifier)
@@ -264,12 +373,25 @@ fn parse_param_list(param_list: Pair<Rule>) -> Vec<&str> {
 }
 
 fn parse_func_decl(declaration: Pair<Rule>) -> ParserNode {
+    let span = span_of(&declaration);
     let pairs = pairs_to_vec(declaration);
 
     ParserNode::FunctionDeclaration(
         pairs[0].as_str(),
         parse_param_list(pairs[1].clone()),
         Box::new(parse_tree_or_expression(pairs[3].clone())),
+        span,
+    )
+}
+
+fn parse_lambda(lambda: Pair<Rule>) -> ParserNode {
+    let source = lambda.as_str().trim_end();
+    let pairs = pairs_to_vec(lambda);
+
+    ParserNode::Lambda(
+        source,
+        parse_param_list(pairs[1].clone()),
+        Box::new(parse_tree_or_expression(pairs[3].clone())),
     )
 }
 
@@ -283,7 +405,7 @@ fn parse_func_call(func_call: Pair<Rule>) -> ParserNode {
         .map(|pair| parse_expression(pair))
         .collect();
 
-    ParserNode::FunctionCall(func_name, arguments)
+    ParserNode::FunctionCall(func_name, arguments, span_of(&pairs[0]))
 }
 
 fn parse_tree_or_expression(tree: Pair<Rule>) -> ParserNode {
@@ -318,11 +440,40 @@ fn parse_conditional<'a>(conditional: Pair<'a, Rule>, predicate: ParserNode<'a>)
 }
 
 fn parse_loop(rloop: Pair<Rule>) -> ParserNode {
-    let pairs = pairs_to_vec(rloop);
-    let range = parse_range(pairs[1].clone());
+    let span = span_of(&rloop);
+    let mut pairs = pairs_to_vec(rloop);
+    let mode = if pairs[1].as_rule() == Rule::loop_mode {
+        parse_loop_mode(pairs.remove(1))
+    } else {
+        LoopMode::Sum
+    };
+
+    let iterable = match pairs[1].as_rule() {
+        Rule::range => parse_range(pairs[1].clone()),
+        _ => parse_value(pairs[1].clone()),
+    };
     let expression = parse_tree_or_expression(pairs[2].clone());
 
-    ParserNode::Loop(pairs[0].as_str(), Box::new(range), Box::new(expression))
+    ParserNode::Loop(pairs[0].as_str(), mode, Box::new(iterable), Box::new(expression), span)
+}
+
+fn parse_loop_mode(mode: Pair<Rule>) -> LoopMode {
+    match mode.as_str() {
+        "sum" => LoopMode::Sum,
+        "product" => LoopMode::Product,
+        "last" => LoopMode::Last,
+        "collect" => LoopMode::Collect,
+        _ => unreachable!(),
+    }
+}
+
+fn parse_while(wloop: Pair<Rule>) -> ParserNode {
+    let span = span_of(&wloop);
+    let pairs = pairs_to_vec(wloop);
+    let predicate = parse_expression(pairs[1].clone());
+    let body = parse_tree_or_expression(pairs[2].clone());
+
+    ParserNode::While(Box::new(predicate), Box::new(body), span)
 }
 
 fn parse_range(range: Pair<Rule>) -> ParserNode {
@@ -340,6 +491,9 @@ fn parse_action(action: Pair<Rule>) -> ParserNode {
         Rule::var_decl => parse_var_decl(action),
         Rule::rloop => parse_loop(action),
         Rule::assignment => parse_assignment(action),
+        Rule::compound_assignment => parse_compound_assignment(action),
+        Rule::global_assignment => parse_global_assignment(action),
+        Rule::destructuring_assignment => parse_destructuring_assignment(action),
         Rule::tree | Rule::expression => parse_tree_or_expression(action),
         _ => unreachable!(),
     }
@@ -354,14 +508,21 @@ fn parse_operator(operator: Pair<Rule>) -> Operator {
         Rule::pow => Operator::Power,
         Rule::abs => Operator::Modulo,
         Rule::eqequals => Operator::Equals,
+        Rule::notequals => Operator::NotEquals,
         Rule::grt => Operator::GreaterThan,
         Rule::lst => Operator::LessThan,
         Rule::gre => Operator::GreaterThanOrEquals,
         Rule::lse => Operator::LessThanOrEquals,
+        Rule::andand => Operator::And,
+        Rule::oror => Operator::Or,
         _ => unreachable!(),
     }
 }
 
+fn span_of(pair: &Pair<Rule>) -> Span {
+    pair.as_span().start_pos().line_col()
+}
+
 fn pairs_to_vec(pair: Pair<Rule>) -> Vec<Pair<Rule>> {
     pair.into_inner().collect::<Vec<Pair<Rule>>>()
 }
@@ -372,12 +533,29 @@ fn get_error_message(input: &str, error: Error<Rule>) -> String {
         LineColLocation::Span(line, col) => (line.0, col.0), // this never gets reached, anyways
     };
 
-    let error_msg = if let ErrorVariant::ParsingError {
+    let error_index = match error.location {
+        InputLocation::Pos(index) => index,
+        InputLocation::Span((start, _)) => start,
+    };
+
+    // a string that is never closed fails to parse where its opening quote is
+    let unterminated_string = input[error_index..].starts_with('"') && !input[error_index + 1..].contains('"');
+
+    let error_msg = if unterminated_string {
+        "unterminated string, this \" is never closed"
+    } else if input[error_index..].starts_with("0x") {
+        "expected hexadecimal digits after 0x"
+    } else if input[error_index..].starts_with("0b") {
+        "expected binary digits after 0b"
+    } else if let ErrorVariant::ParsingError {
         positives,
         negatives: _,
     } = error.variant
     {
-        if positives.iter().any(|rule| rule == &Rule::tree) {
+        // only keywords like let and while are ruled out with nothing expected in their place
+        if positives.is_empty() {
+            "keywords cannot be used as names"
+        } else if positives.iter().any(|rule| rule == &Rule::tree) {
             "expected a } here"
         } else if positives.iter().any(|rule| rule == &Rule::comma) {
             "expected a , here"
@@ -389,7 +567,7 @@ fn get_error_message(input: &str, error: Error<Rule>) -> String {
             match positives[0] {
                 Rule::value => "expected proper value",
                 Rule::identifier => "expected a variable name here",
-                Rule::expression => "expected an expression",
+                Rule::expression | Rule::comparison | Rule::sum => "expected an expression",
                 Rule::equals => "expected = here after let declaration",
                 Rule::rarrow => "expected => here",
                 Rule::lcurlb => "expected tree or expression",
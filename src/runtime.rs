@@ -1,30 +1,146 @@
-use crate::parser::{Operator, ParserNode};
+use crate::parser::{LoopMode, Operator, ParserNode};
 use num_complex::Complex64;
-use std::{collections::HashMap, fmt, ops};
+use std::{collections::HashMap, convert::TryFrom, fmt, ops, sync::Arc};
 use Value::*;
-use std::time::Instant;
-
+use ErrorKind::*;
+use std::time::{Duration, Instant};
+
+/// A value a Leibniz script works with, such as the result of running it.
+///
+/// ```
+/// use leibniz::Value;
+///
+/// let value = leibniz::evaluate_str("[1, 2i]").unwrap();
+/// assert_eq!(value, Value::array(vec![Value::real(1.0), Value::imaginary(2.0)]));
+/// assert_eq!(value.to_string(), "[1, 2i]");
+/// ```
 #[derive(Debug)]
 pub enum Value {
     Number(Complex64),
-    Vector(f64, f64),
-    Array(Vec<Value>)
+    Vector(Vec<f64>),
+    Array(Arc<Vec<Value>>), // shared, so that passing an array around does not copy its elements
+    Boolean(bool),
+    Function(String), // a reference to a builtin or declared function, by name
+    Str(String),
+    Matrix(Vec<Vec<Complex64>>), // stored as a list of rows, which are all the same length
+    Rational(i64, i64) // an exact fraction in lowest terms, with a positive denominator
+}
+
+type ValueOutput = Result<Value, RuntimeError>;
+
+// what went wrong when a leibniz script stopped.
+// displaying it gives the message on its own, without where it happened
+#[derive(Debug, PartialEq)]
+pub enum ErrorKind {
+    TypeMismatch(String),                // a value of the wrong type was used, described by the message
+    UndefinedVariable(String),           // the name of a variable that does not exist
+    UndefinedFunction(String),           // the name of a function that does not exist
+    ArityMismatch(String, usize, usize), // a function name, the number of parameters it expects and the number supplied
+    IndexOutOfBounds(f64, usize),        // an index and the length of the array it was used on
+    DivisionByZero,
+    RecursionLimitExceeded(usize),       // the maximum depth of function calls that was exceeded
+    BudgetExceeded(usize),               // the maximum number of evaluation steps that was exceeded
+    Timeout(Duration),                   // the longest the script was allowed to run for
+    ElementLimitExceeded(usize),         // the most elements an array or matrix made in one go was allowed to have
+    Other(String),                       // any other error, described by the message
+}
+
+// an error that stops a leibniz script while it is running.
+// displaying an error gives the message that is shown to the user, followed by where it happened if that is known
+#[derive(Debug, PartialEq)]
+pub struct RuntimeError {
+    pub kind: ErrorKind,
+    pub location: Option<(usize, usize)>, // the line and column of the innermost code that knows where it is
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeMismatch(message) | Other(message) => write!(f, "{}", message),
+            UndefinedVariable(name) => write!(f, "unknown variable: {}", name),
+            UndefinedFunction(name) => write!(f, "unknown function: {}", name),
+            ArityMismatch(name, expected, supplied) => {
+                write!(f, "{} expects {} parameters, but {} were supplied", name, expected, supplied)
+            }
+            IndexOutOfBounds(index, length) => {
+                write!(f, "attempted to index array of length {} with index {}", length, index)
+            }
+            DivisionByZero => write!(f, "cannot divide by zero"),
+            BudgetExceeded(limit) => write!(f, "exceeded the limit of {} evaluation steps", limit),
+            Timeout(timeout) => write!(f, "exceeded the time limit of {} seconds", timeout.as_secs_f64()),
+            ElementLimitExceeded(limit) => write!(f, "exceeded the limit of {} elements in a single array or matrix", limit),
+            RecursionLimitExceeded(limit) => {
+                write!(f, "exceeded the maximum depth of {} nested function calls, there may be infinite recursion", limit)
+            }
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(f, "{} at line {}, column {}", self.kind, line, column),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl From<ErrorKind> for RuntimeError {
+    fn from(kind: ErrorKind) -> Self {
+        RuntimeError { kind, location: None }
+    }
+}
+
+impl From<&str> for RuntimeError {
+    fn from(message: &str) -> Self {
+        Other(message.to_string()).into()
+    }
 }
 
-type ValueOutput = Result<Value, String>;
+impl From<String> for RuntimeError {
+    fn from(message: String) -> Self {
+        Other(message).into()
+    }
+}
 
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match self {
             Number(n) => match other {
                 Number(n2) => n.re == n2.re && n.im == n2.im,
+                Rational(_, _) => other == self,
                 _ => false,
             },
-            Vector(x, y) => match other {
-                Vector(x2, y2) => x == x2 && y == y2,
+            Vector(v) => match other {
+                Vector(v2) => v == v2,
+                _ => false,
+            }
+            Array(arr) => match other {
+                Array(arr2) => arr.len() == arr2.len() && arr.iter().zip(arr2.iter()).all(|(a, b)| a == b),
+                _ => false,
+            }
+            Boolean(b) => match other {
+                Boolean(b2) => b == b2,
+                _ => false,
+            }
+            Function(name) => match other {
+                Function(name2) => name == name2,
+                _ => false,
+            }
+            Matrix(rows) => match other {
+                Matrix(rows2) => rows == rows2,
+                _ => false,
+            }
+            Str(string) => match other {
+                Str(string2) => string == string2,
+                _ => false,
+            }
+            // a fraction is equal to the number it stands for
+            Rational(numerator, denominator) => match other {
+                Rational(numerator2, denominator2) => numerator == numerator2 && denominator == denominator2,
+                Number(n) => n.im == 0.0 && n.re == *numerator as f64 / *denominator as f64,
                 _ => false,
             }
-            Array(_) => false
         }
     }
 }
@@ -35,18 +151,38 @@ impl ops::Add<Value> for Value {
     type Output = ValueOutput;
 
     fn add(self, rhs: Value) -> Self::Output {
+        // strings concatenate with the displayed form of any value, on either side
+        if matches!(self, Str(_)) || matches!(rhs, Str(_)) {
+            return Ok(Str(format!("{}{}", self, rhs)));
+        }
+
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::Add, |a, b| a + b) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
                 Number(c2) => Ok(Number(c + c2)),
-                Vector(_, _) => Err("cannot add a number to a vector".into()),
-                Array(_) => Ok(rhs.push(self))
+                Vector(_) => Err(TypeMismatch("cannot add a number to a vector".into()).into()),
+                Array(arr) => Value::broadcast(arr, self, |element, number| number + element),
+                _ => Err(TypeMismatch(format!("cannot add {} to {}", self.type_name(), rhs.type_name())).into()),
+            },
+            Vector(v) => match rhs {
+                Number(_) => Err(TypeMismatch("cannot add a vector to a number".into()).into()),
+                Vector(v2) => Value::component_wise(v, v2, "add", |a, b| a + b),
+                Array(arr) => Value::broadcast(arr, Vector(v), |element, vector| vector + element),
+                _ => Err(TypeMismatch(format!("cannot add a vector to {}", rhs.type_name())).into()),
             },
-            Vector(x, y) => match rhs {
-                Number(_) => Err("cannot add a vector to a number".into()),
-                Vector(x2, y2) => Ok(Vector(x + x2, y + y2)),
-                Array(_) => Ok(rhs.push(self)),
+            Array(arr) => match rhs {
+                Number(_) | Vector(_) => Value::broadcast(arr, rhs, |element, scalar| element + scalar),
+                Array(arr2) => Value::element_wise(arr, arr2, "add", |a, b| a + b),
+                _ => Err(TypeMismatch(format!("cannot add an array to {}", rhs.type_name())).into()),
             },
-            Array(_) => Ok(self.push(rhs))
+            Matrix(rows) => match rhs {
+                Matrix(rows2) => Value::matrix_element_wise(rows, rows2, "add", |a, b| a + b),
+                _ => Err(TypeMismatch(format!("cannot add a matrix to {}", rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot add {} to {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 }
@@ -55,22 +191,50 @@ impl ops::Sub<Value> for Value {
     type Output = ValueOutput;
 
     fn sub(self, rhs: Value) -> Self::Output {
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::Subtract, |a, b| a - b) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
                 Number(c2) => Ok(Number(c - c2)),
-                Vector(_, _) => Err("cannot subtract a number from a vector".into()),
-                Array(_) => Err("cannot subtract an array from a number".into()),
+                Vector(_) => Err(TypeMismatch("cannot subtract a number from a vector".into()).into()),
+                Array(arr) => Value::broadcast(arr, self, |element, number| number - element),
+                _ => Err(TypeMismatch(format!("cannot subtract {} from {}", rhs.type_name(), self.type_name())).into()),
             },
-            Vector(x, y) => match rhs {
-                Number(_) => Err("cannot subtract a vector from a number".into()),
-                Vector(x2, y2) => Ok(Vector(x - x2, y - y2)),
-                Array(_) => Err("cannot subtract an array from a vector".into()),
+            Vector(v) => match rhs {
+                Number(_) => Err(TypeMismatch("cannot subtract a vector from a number".into()).into()),
+                Vector(v2) => Value::component_wise(v, v2, "subtract", |a, b| a - b),
+                Array(arr) => Value::broadcast(arr, Vector(v), |element, vector| vector - element),
+                _ => Err(TypeMismatch(format!("cannot subtract {} from a vector", rhs.type_name())).into()),
             },
-            Array(_) => match rhs {
-                Number(_) => Err("cannot subtract a number from an array".into()),
-                Vector(_, _) => Err("cannot subtract a vector from an array".into()),
-                Array(_) => Err("cannot subtract an array from an array".into())
-            }
+            Array(arr) => match rhs {
+                Number(_) | Vector(_) => Value::broadcast(arr, rhs, |element, scalar| element - scalar),
+                Array(arr2) => Value::element_wise(arr, arr2, "subtract", |a, b| a - b),
+                _ => Err(TypeMismatch(format!("cannot subtract {} from an array", rhs.type_name())).into()),
+            },
+            Matrix(rows) => match rhs {
+                Matrix(rows2) => Value::matrix_element_wise(rows, rows2, "subtract", |a, b| a - b),
+                _ => Err(TypeMismatch(format!("cannot subtract {} from a matrix", rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot subtract {} from {}", rhs.type_name(), self.type_name())).into()),
+        }
+    }
+}
+
+impl ops::Neg for Value {
+    type Output = ValueOutput;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            // taken away from zero like subtraction does, so real numbers keep a positive zero imaginary part
+            // and (-1)^0.5 is i rather than -i
+            Number(c) => Ok(Number(Complex64::new(0.0, 0.0) - c)),
+            Rational(n, d) => Value::rational(-(n as i128), d as i128),
+            Vector(v) => Ok(Vector(v.iter().map(|x| -x).collect())),
+            Array(arr) => Ok(Value::array(Arc::unwrap_or_clone(arr).into_iter().map(|element| -element).collect::<Result<_, _>>()?)),
+            Matrix(rows) => Ok(Matrix(rows.iter().map(|row| row.iter().map(|c| -c).collect()).collect())),
+            _ => Err(TypeMismatch(format!("cannot negate {}", self.type_name())).into()),
         }
     }
 }
@@ -79,32 +243,47 @@ impl ops::Mul<Value> for Value {
     type Output = ValueOutput;
 
     fn mul(self, rhs: Value) -> Self::Output {
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::Multiply, |a, b| a * b) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
+                // real numbers skip complex multiplication, which turns inf * 0i into NaN
+                Number(c2) if c.im == 0.0 && c2.im == 0.0 => Ok(Value::real(c.re * c2.re)),
                 Number(c2) => Ok(Number(c * c2)),
-                Vector(x, y) => if c.im != 0.0 {
-                    Err("cannot multiply a vector with a complex number".into())
+                Vector(v) => if c.im != 0.0 {
+                    Err(TypeMismatch("cannot multiply a vector with a complex number".into()).into())
                 }
                 else {
-                    Ok(Vector(x * c.re, y * c.re))
+                    Ok(Vector(v.iter().map(|x| x * c.re).collect()))
                 },
-                Array(_) => Err("cannot multiply a number by an array".into())
+                Array(arr) => Value::broadcast(arr, self, |element, number| number * element),
+                Matrix(rows) => Ok(Value::scale_matrix(rows, c)),
+                _ => Err(TypeMismatch(format!("cannot multiply {} with {}", self.type_name(), rhs.type_name())).into()),
             },
-            Vector(x, y) => match rhs {
+            Vector(v) => match rhs {
                 Number(c) => if c.im != 0.0 {
-                    Err("cannot multiply a vector with a complex number".into())
+                    Err(TypeMismatch("cannot multiply a vector with a complex number".into()).into())
                 }
                 else {
-                    Ok(Vector(x * c.re, y * c.re))
+                    Ok(Vector(v.iter().map(|x| x * c.re).collect()))
                 }
-                Vector(_, _) => Err("cannot multiply a vector with a vector. use dot(vector, vector) or cross(vector, vector) instead".into()),
-                Array(_) => Err("cannot multiply an array with a vector".into())
+                Vector(_) => Err(TypeMismatch("cannot multiply a vector with a vector. use dot(vector, vector) or cross(vector, vector) instead".into()).into()),
+                Array(arr) => Value::broadcast(arr, Vector(v), |element, vector| vector * element),
+                _ => Err(TypeMismatch(format!("cannot multiply a vector with {}", rhs.type_name())).into()),
             },
-            Array(_) => match rhs {
-                Number(_) => Err("cannot multiply an array by a number".into()),
-                Vector(_, _) => Err("cannot multiply an array with a vector".into()),
-                Array(_) => Err("cannot multiply an array by an array".into())
-            }
+            Array(arr) => match rhs {
+                Number(_) | Vector(_) => Value::broadcast(arr, rhs, |element, scalar| element * scalar),
+                Array(arr2) => Value::element_wise(arr, arr2, "multiply", |a, b| a * b),
+                _ => Err(TypeMismatch(format!("cannot multiply an array with {}", rhs.type_name())).into()),
+            },
+            Matrix(rows) => match rhs {
+                Number(c) => Ok(Value::scale_matrix(rows, c)),
+                Matrix(rows2) => Value::matrix_product(rows, rows2),
+                _ => Err(TypeMismatch(format!("cannot multiply a matrix with {}", rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot multiply {} with {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 }
@@ -113,34 +292,45 @@ impl ops::Div<Value> for Value {
     type Output = ValueOutput;
 
     fn div(self, rhs: Value) -> Self::Output {
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::Divide, |a, b| a / b) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
-                Number(c2) => Ok(Number(c / c2)),
-                Vector(x, y) => {
+                Number(c2) => if c.im == 0.0 && c2.im == 0.0 {
+                    Ok(Value::real(c.re / c2.re))
+                } else {
+                    Ok(Number(c / c2))
+                },
+                Vector(v) => {
                     if c.im != 0.0 {
-                        Err("cannot divide a vector by a complex number".into())
+                        Err(TypeMismatch("cannot divide a vector by a complex number".into()).into())
                     } else {
-                        Ok(Vector(x / c.re, y / c.re))
+                        Ok(Vector(v.iter().map(|x| x / c.re).collect()))
                     }
                 },
-                Array(_) => Err("cannot divide a number by an array".into())
+                Array(arr) => Value::broadcast(arr, self, |element, number| number / element),
+                _ => Err(TypeMismatch(format!("cannot divide {} by {}", self.type_name(), rhs.type_name())).into()),
             },
-            Vector(x, y) => match rhs {
+            Vector(v) => match rhs {
                 Number(c) => {
                     if c.im != 0.0 {
-                        Err("cannot divide a vector by a complex number".into())
+                        Err(TypeMismatch("cannot divide a vector by a complex number".into()).into())
                     } else {
-                        Ok(Vector(x / c.re, y / c.re))
+                        Ok(Vector(v.iter().map(|x| x / c.re).collect()))
                     }
                 }
-                Vector(_, _) => Err("cannot divide a vector by a vector".into()),
-                Array(_) => Err("cannot divide a vector by an array".into())
+                Vector(_) => Err(TypeMismatch("cannot divide a vector by a vector".into()).into()),
+                Array(arr) => Value::broadcast(arr, Vector(v), |element, vector| vector / element),
+                _ => Err(TypeMismatch(format!("cannot divide a vector by {}", rhs.type_name())).into()),
             },
-            Array(_) => match rhs {
-                Number(_) => Err("cannot divide an array by a number".into()),
-                Vector(_, _) => Err("cannot divide an array by a vector".into()),
-                Array(_) => Err("cannot divide an array by an array".into())
-            }
+            Array(arr) => match rhs {
+                Number(_) | Vector(_) => Value::broadcast(arr, rhs, |element, scalar| element / scalar),
+                Array(arr2) => Value::element_wise(arr, arr2, "divide", |a, b| a / b),
+                _ => Err(TypeMismatch(format!("cannot divide an array by {}", rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot divide {} by {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 }
@@ -151,28 +341,151 @@ impl ops::Rem<Value> for Value {
     fn rem(self, rhs: Value) -> Self::Output {
         use Value::*;
 
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::Modulo, |a, b| a % b) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
-                Number(c2) => Ok(Number(c % c2)),
-                Vector(_, _) => Err("cannot find remainder between number and vector".into()),
-                Array(_) => Err("cannot find remainder of number in terms of array".into())
+                // the remainder of complex numbers isn't well defined, so only real numbers are allowed
+                Number(c2) => if c.im != 0.0 || c2.im != 0.0 {
+                    Err(TypeMismatch(format!("cannot find remainder between {} and {}, remainders only work on real numbers", self, rhs)).into())
+                } else if c2.re == 0.0 {
+                    Err(DivisionByZero.into())
+                } else {
+                    Ok(Value::real(c.re % c2.re))
+                },
+                Vector(_) => Err(TypeMismatch("cannot find remainder between number and vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot find remainder of number in terms of array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot find remainder between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
-            Vector(_, _) => match rhs {
-                Number(_) => Err("cannot find remainder between vector and number".into()),
-                Vector(_, _) => Err("cannot find remainder between vector and vector".into()),
-                Array(_) => Err("cannot find remainder between vector and array".into())
+            Vector(_) => match rhs {
+                Number(_) => Err(TypeMismatch("cannot find remainder between vector and number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot find remainder between vector and vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot find remainder between vector and array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot find remainder between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
             Array(_) => match rhs {
-                Number(_) => Err("cannot find remainder between arraay and number".into()),
-                Vector(_, _) => Err("cannot find remainder between array and number".into()),
-                Array(_) => Err("cannot find remainder between array and array".into())
-            }
+                Number(_) => Err(TypeMismatch("cannot find remainder between arraay and number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot find remainder between array and number".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot find remainder between array and array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot find remainder between {} and {}", self.type_name(), rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot find remainder between {} and {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 }
 
 impl Value {
+    // applies an operator to each pair of elements in two arrays of the same length
+    fn element_wise(arr: Arc<Vec<Value>>, arr2: Arc<Vec<Value>>, verb: &str, operator: fn(Value, Value) -> ValueOutput) -> ValueOutput {
+        if arr.len() != arr2.len() {
+            return Err(format!("cannot {} arrays of different lengths {} and {}", verb, arr.len(), arr2.len()).into());
+        }
+
+        Ok(Value::array(Arc::unwrap_or_clone(arr).into_iter().zip(Arc::unwrap_or_clone(arr2)).map(|(a, b)| operator(a, b)).collect::<Result<_, _>>()?))
+    }
+
+    // applies an operator to each pair of components in two vectors of the same dimension
+    fn component_wise(v: Vec<f64>, v2: Vec<f64>, verb: &str, operator: fn(f64, f64) -> f64) -> ValueOutput {
+        if v.len() != v2.len() {
+            return Err(format!("cannot {} vectors of different dimensions {} and {}", verb, v.len(), v2.len()).into());
+        }
+
+        Ok(Vector(v.into_iter().zip(v2).map(|(a, b)| operator(a, b)).collect()))
+    }
+
+    // the shape of a matrix as rows by columns, used for error messages
+    fn shape(rows: &[Vec<Complex64>]) -> String {
+        format!("{}x{}", rows.len(), rows[0].len())
+    }
+
+    // applies an operator to each pair of elements in two matrices of the same shape
+    fn matrix_element_wise(rows: Vec<Vec<Complex64>>, rows2: Vec<Vec<Complex64>>, verb: &str, operator: fn(Complex64, Complex64) -> Complex64) -> ValueOutput {
+        if rows.len() != rows2.len() || rows[0].len() != rows2[0].len() {
+            return Err(format!("cannot {} matrices of different shapes {} and {}", verb, Value::shape(&rows), Value::shape(&rows2)).into());
+        }
+
+        Ok(Matrix(rows.into_iter().zip(rows2).map(|(row, row2)| {
+            row.into_iter().zip(row2).map(|(a, b)| operator(a, b)).collect()
+        }).collect()))
+    }
+
+    fn scale_matrix(rows: Vec<Vec<Complex64>>, c: Complex64) -> Value {
+        Matrix(rows.into_iter().map(|row| row.into_iter().map(|x| x * c).collect()).collect())
+    }
+
+    fn matrix_product(rows: Vec<Vec<Complex64>>, rows2: Vec<Vec<Complex64>>) -> ValueOutput {
+        if rows[0].len() != rows2.len() {
+            return Err(format!("cannot multiply a {} matrix with a {} matrix, the inner dimensions differ", Value::shape(&rows), Value::shape(&rows2)).into());
+        }
+
+        Ok(Matrix(rows.iter().map(|row| {
+            (0..rows2[0].len()).map(|j| row.iter().zip(&rows2).map(|(a, row2)| a * row2[j]).sum()).collect()
+        }).collect()))
+    }
+
+    // applies an operator between each element of an array and a single value.
+    // the operator always receives the element first, whichever side of the operation it was on
+    fn broadcast(arr: Arc<Vec<Value>>, value: Value, operator: fn(Value, Value) -> ValueOutput) -> ValueOutput {
+        Ok(Value::array(Arc::unwrap_or_clone(arr).into_iter().map(|element| operator(element, value.clone())).collect::<Result<_, _>>()?))
+    }
+
+    // arithmetic between fractions, or a fraction and an integer, gives an exact fraction when it can.
+    // otherwise a fraction is treated as the number it stands for. returns None when neither side is a fraction
+    fn rational_operation(
+        lhs: &Value,
+        rhs: &Value,
+        operator: Operator,
+        fallback: fn(Value, Value) -> ValueOutput
+    ) -> Option<ValueOutput> {
+        if !matches!(lhs, Rational(_, _)) && !matches!(rhs, Rational(_, _)) {
+            return None;
+        }
+
+        if let Some((numerator, denominator)) = lhs.as_fraction().zip(rhs.as_fraction()).and_then(|(x, y)| fraction_operation(operator, x, y)) {
+            return Some(Value::rational(numerator, denominator));
+        }
+
+        Some(fallback(lhs.without_rational(), rhs.without_rational()))
+    }
+
+    // a fraction or an integer as a numerator and denominator
+    fn as_fraction(&self) -> Option<(i128, i128)> {
+        match self {
+            Rational(numerator, denominator) => Some((*numerator as i128, *denominator as i128)),
+            Number(c) if c.im == 0.0 && c.re.fract() == 0.0 && c.re.abs() < i64::MAX as f64 => Some((c.re as i128, 1)),
+            _ => None
+        }
+    }
+
+    fn without_rational(&self) -> Value {
+        match self {
+            Rational(numerator, denominator) => Value::real(*numerator as f64 / *denominator as f64),
+            _ => self.clone()
+        }
+    }
+
+    // a fraction in lowest terms, or the number it stands for if it is too big to be stored as one
+    fn rational(numerator: i128, denominator: i128) -> ValueOutput {
+        if denominator == 0 {
+            return Err(DivisionByZero.into());
+        }
+
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+
+        match (i64::try_from(numerator), i64::try_from(denominator)) {
+            (Ok(numerator), Ok(denominator)) => Ok(Rational(numerator, denominator)),
+            _ => Ok(Value::real(numerator as f64 / denominator as f64))
+        }
+    }
+
     fn pow(self, rhs: Value) -> ValueOutput {
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::Power, Value::pow) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
                 Number(c2) => {
@@ -184,25 +497,29 @@ impl Value {
                         Ok(Number(c.powc(c2)))
                     }
                 }
-                Vector(_, _) => Err("cannot raise a number to a vector power".into()),
-                Array(_) => Err("cannot raise a number to an array power".into())
+                Vector(_) => Err(TypeMismatch("cannot raise a number to a vector power".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot raise a number to an array power".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot raise {} to the power of {}", self.type_name(), rhs.type_name())).into()),
             },
-            Vector(x, y) => match rhs {
+            Vector(v) => match rhs {
                 Number(c) => {
                     if c.im != 0.0 {
-                        Err("cannot raise vector to a complex power".into())
+                        Err(TypeMismatch("cannot raise vector to a complex power".into()).into())
                     } else {
-                        Ok(Vector(x.powf(c.re), y.powf(c.re)))
+                        Ok(Vector(v.iter().map(|x| x.powf(c.re)).collect()))
                     }
                 }
-                Vector(_, _) => Err("cannot raise a vector to a vector power".into()),
-                Array(_) => Err("cannot raise a vector to an array power".into())
+                Vector(_) => Err(TypeMismatch("cannot raise a vector to a vector power".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot raise a vector to an array power".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot raise a vector to the power of {}", rhs.type_name())).into()),
             },
             Array(_) => match rhs {
-                Number(_) => Err("cannot raise array to a number power".into()),
-                Vector(_, _) => Err("cannot raise array to a vector power".into()),
-                Array(_) => Err("cannot raise array to an array power".into())
-            }
+                Number(_) => Err(TypeMismatch("cannot raise array to a number power".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot raise array to a vector power".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot raise array to an array power".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot raise {} to the power of {}", self.type_name(), rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot raise {} to the power of {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 
@@ -211,139 +528,319 @@ impl Value {
     // but equals will work for all. a vector being greater than an imaginary is undefined,
     // but a vector being equal to an imaginary is very clearly false.
     fn equals(self, rhs: Value) -> Value {
-        match self == rhs {
-            true => Value::real(1.0),
-            false => Value::real(0.0)
-        }
+        Boolean(self == rhs)
+    }
+
+    fn not_equals(self, rhs: Value) -> Value {
+        Boolean(self != rhs)
     }
 
     fn greater_than(self, rhs: Value) -> ValueOutput {
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::GreaterThan, Value::greater_than) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
-                Number(c2) => Ok(if c.norm() > c2.norm() {
-                    Number(Complex64::new(1.0, 0.0))
-                } else {
-                    Number(Complex64::new(0.0, 0.0))
-                }),
-                Vector(_, _) => Err("cannot compare greater-than between a number and vector".into()),
-                Array(_) => Err("cannot compare greater-than between a number and array".into())
+                Number(c2) => {
+                    let (a, b) = Value::ordered_pair(c, c2)?;
+                    Ok(Boolean(a > b))
+                }
+                Vector(_) => Err(TypeMismatch("cannot compare greater-than between a number and vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare greater-than between a number and array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare greater-than between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
-            Vector(_, _) => match rhs {
-                Number(_) => Err("cannot compare greater-than between a vector and a number".into()),
-                Vector(_, _) => Err("cannot compare greater-than between a vector and a vector".into()),
-                Array(_) => Err("cannot compare greater-than between a vector and an array".into())
+            Vector(_) => match rhs {
+                Number(_) => Err(TypeMismatch("cannot compare greater-than between a vector and a number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot compare greater-than between a vector and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare greater-than between a vector and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare greater-than between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
             Array(_) => match rhs {
-                Number(_) => Err("cannot compare greater-than between an array and a number".into()),
-                Vector(_, _) => Err("cannot compare greater-than between an array and a vector".into()),
-                Array(_) => Err("cannot compare greater-than between an array and an array".into())
-            }
+                Number(_) => Err(TypeMismatch("cannot compare greater-than between an array and a number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot compare greater-than between an array and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare greater-than between an array and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare greater-than between {} and {}", self.type_name(), rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot compare greater-than between {} and {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 
     fn less_than(self, rhs: Value) -> ValueOutput {
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::LessThan, Value::less_than) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
-                Number(c2) => Ok(if c.norm() < c2.norm() {
-                    Number(Complex64::new(1.0, 0.0))
-                } else {
-                    Number(Complex64::new(0.0, 0.0))
-                }),
-                Vector(_, _) => Err("cannot compare less-than between a number and a vector".into()),
-                Array(_) => Err("cannot compare less-than between a number and an array".into())
+                Number(c2) => {
+                    let (a, b) = Value::ordered_pair(c, c2)?;
+                    Ok(Boolean(a < b))
+                }
+                Vector(_) => Err(TypeMismatch("cannot compare less-than between a number and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare less-than between a number and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare less-than between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
-            Vector(_, _) => match rhs {
-                Number(_) => Err("cannot compare less-than between a vector and a number".into()),
-                Vector(_, _) => Err("cannot compare less-than between a vector and a vector".into()),
-                Array(_) => Err("cannot compare less-than between a vector and an array".into())
+            Vector(_) => match rhs {
+                Number(_) => Err(TypeMismatch("cannot compare less-than between a vector and a number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot compare less-than between a vector and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare less-than between a vector and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare less-than between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
             Array(_) => match rhs {
-                Number(_) => Err("cannot compare less-than between an array and a number".into()),
-                Vector(_, _) => Err("cannot compare less-than between an array and a vector".into()),
-                Array(_) => Err("cannot compare less-than between an array and an array".into())
-            }
+                Number(_) => Err(TypeMismatch("cannot compare less-than between an array and a number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot compare less-than between an array and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare less-than between an array and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare less-than between {} and {}", self.type_name(), rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot compare less-than between {} and {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 
     fn greater_than_or_equals(self, rhs: Value) -> ValueOutput {
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::GreaterThanOrEquals, Value::greater_than_or_equals) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
-                Number(c2) => Ok(if c.norm() >= c2.norm() {
-                    Number(Complex64::new(1.0, 0.0))
-                } else {
-                    Number(Complex64::new(0.0, 0.0))
-                }),
-                Vector(_, _) => Err("cannot compare greater-than-or-equals between a number and vector".into()),
-                Array(_) => Err("cannot compare greater-than-or-equals between a number and an array".into())
+                Number(c2) => {
+                    let (a, b) = Value::ordered_pair(c, c2)?;
+                    Ok(Boolean(a >= b))
+                }
+                Vector(_) => Err(TypeMismatch("cannot compare greater-than-or-equals between a number and vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare greater-than-or-equals between a number and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare greater-than-or-equals between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
-            Vector(_, _) => match rhs {
-                Number(_) => Err("cannot compare greater-than-or-equals between a vector and a number".into()),
-                Vector(_, _) => Err("cannot compare greater-than-or-equals between a vector and a vector".into()),
-                Array(_) => Err("cannot compare greater-than-or-equals between a vector and an array".into())
+            Vector(_) => match rhs {
+                Number(_) => Err(TypeMismatch("cannot compare greater-than-or-equals between a vector and a number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot compare greater-than-or-equals between a vector and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare greater-than-or-equals between a vector and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare greater-than-or-equals between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
             Array(_) => match rhs {
-                Number(_) => Err("cannot compare greater-than-or-equals between an array and a number".into()),
-                Vector(_, _) => Err("cannot compare greater-than-or-equals between an array and a vector".into()),
-                Array(_) => Err("cannot compare greater-than-or-equals between an array and an array".into())
-            }
+                Number(_) => Err(TypeMismatch("cannot compare greater-than-or-equals between an array and a number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot compare greater-than-or-equals between an array and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare greater-than-or-equals between an array and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare greater-than-or-equals between {} and {}", self.type_name(), rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot compare greater-than-or-equals between {} and {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 
     fn less_than_or_equals(self, rhs: Value) -> ValueOutput {
+        if let Some(result) = Value::rational_operation(&self, &rhs, Operator::LessThanOrEquals, Value::less_than_or_equals) {
+            return result;
+        }
+
         match self {
             Number(c) => match rhs {
-                Number(c2) => Ok(if c.norm() <= c2.norm() {
-                    Number(Complex64::new(1.0, 0.0))
-                } else {
-                    Number(Complex64::new(0.0, 0.0))
-                }),
-                Vector(_, _) => Err("cannot compare less-than-or-equals between a number and vector".into()),
-                Array(_) => Err("cannot compare less-than-or-equals between a number and an array".into())
+                Number(c2) => {
+                    let (a, b) = Value::ordered_pair(c, c2)?;
+                    Ok(Boolean(a <= b))
+                }
+                Vector(_) => Err(TypeMismatch("cannot compare less-than-or-equals between a number and vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare less-than-or-equals between a number and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare less-than-or-equals between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
-            Vector(_, _) => match rhs {
-                Number(_) => Err("cannot compare less-than-or-equals between a vector and a number".into()),
-                Vector(_, _) => Err("cannot compare less-than-or-equals between a vector and a vector".into()),
-                Array(_) => Err("cannot compare less-than-or-equals between a vector and an array".into())
+            Vector(_) => match rhs {
+                Number(_) => Err(TypeMismatch("cannot compare less-than-or-equals between a vector and a number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot compare less-than-or-equals between a vector and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare less-than-or-equals between a vector and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare less-than-or-equals between {} and {}", self.type_name(), rhs.type_name())).into()),
             },
             Array(_) => match rhs {
-                Number(_) => Err("cannot compare less-than-or-equals between an array and a number".into()),
-                Vector(_, _) => Err("cannot compare less-than-or-equals between an array and a vector".into()),
-                Array(_) => Err("cannot compare less-than-or-equals between an array and an array".into())
-            }
+                Number(_) => Err(TypeMismatch("cannot compare less-than-or-equals between an array and a number".into()).into()),
+                Vector(_) => Err(TypeMismatch("cannot compare less-than-or-equals between an array and a vector".into()).into()),
+                Array(_) => Err(TypeMismatch("cannot compare less-than-or-equals between an array and an array".into()).into()),
+                _ => Err(TypeMismatch(format!("cannot compare less-than-or-equals between {} and {}", self.type_name(), rhs.type_name())).into()),
+            },
+            _ => Err(TypeMismatch(format!("cannot compare less-than-or-equals between {} and {}", self.type_name(), rhs.type_name())).into()),
         }
     }
 
-    fn expect_real<'a>(&self, message: &'a str) -> Result<f64, &'a str> {
+    /// Gives back the value as a real number, or a type mismatch with `message` if it isn't one.
+    ///
+    /// ```
+    /// use leibniz::Value;
+    ///
+    /// assert_eq!(Value::real(2.5).expect_real("expected a real number"), Ok(2.5));
+    /// assert!(Value::imaginary(1.0).expect_real("expected a real number").is_err());
+    /// ```
+    pub fn expect_real(&self, message: &str) -> Result<f64, RuntimeError> {
         match self {
             Number(c) => {
                 if c.im == 0.0 {
                     Ok(c.re)
                 } else {
-                    Err(message)
+                    Err(TypeMismatch(message.into()).into())
                 }
             }
-            _ => Err(message),
+            Rational(numerator, denominator) => Ok(*numerator as f64 / *denominator as f64),
+            _ => Err(TypeMismatch(message.into()).into()),
         }
     }
 
-    fn expect_complex<'a>(&self, message: &'a str) -> Result<Complex64, &'a str> {
+    pub fn expect_complex(&self, message: &str) -> Result<Complex64, RuntimeError> {
         match self {
             Number(c) => Ok(*c),
-            _ => Err(message),
+            Rational(numerator, denominator) => Ok(Complex64::new(*numerator as f64 / *denominator as f64, 0.0)),
+            _ => Err(TypeMismatch(message.into()).into()),
+        }
+    }
+
+    pub fn expect_vector(&self, message: &str) -> Result<&Vec<f64>, RuntimeError> {
+        match self {
+            Vector(v) => Ok(v),
+            _ => Err(TypeMismatch(message.into()).into()),
+        }
+    }
+
+    // a matrix dimension must be a whole number of at least one
+    fn expect_dimension(&self, message: &str) -> Result<usize, RuntimeError> {
+        let n = self.expect_real(message)?;
+
+        if n.fract() != 0.0 || n < 1.0 {
+            Err(TypeMismatch(message.into()).into())
+        } else {
+            Ok(n as usize)
+        }
+    }
+
+    // a count of things must be a whole number, but can be zero
+    fn expect_count(&self, message: &str) -> Result<usize, RuntimeError> {
+        let n = self.expect_real(message)?;
+
+        if n.fract() != 0.0 || n < 0.0 {
+            Err(TypeMismatch(message.into()).into())
+        } else {
+            Ok(n as usize)
+        }
+    }
+
+    fn expect_integer(&self, message: &str) -> Result<i64, RuntimeError> {
+        let n = self.expect_real(message)?;
+
+        // i64::MAX as a float rounds up to 2^63, which is already too big, so the top of the range is left out
+        if n.fract() != 0.0 || !(i64::MIN as f64..i64::MAX as f64).contains(&n) {
+            Err(TypeMismatch(message.into()).into())
+        } else {
+            Ok(n as i64)
+        }
+    }
+
+    pub fn expect_matrix(&self, message: &str) -> Result<&Vec<Vec<Complex64>>, RuntimeError> {
+        match self {
+            Matrix(rows) => Ok(rows),
+            _ => Err(TypeMismatch(message.into()).into()),
         }
     }
 
-    fn expect_vector<'a>(&self, message: &'a str) -> Result<(f64, f64), &'a str> {
+    pub fn expect_string(&self, message: &str) -> Result<&str, RuntimeError> {
         match self {
-            Vector(x, y) => Ok((*x, *y)),
-            _ => Err(message),
+            Str(string) => Ok(string),
+            _ => Err(TypeMismatch(message.into()).into())
         }
     }
 
-    fn expect_array<'a>(&self, message: &'a str) -> Result<&Vec<Value>, &'a str> {
+    pub fn expect_array(&self, message: &str) -> Result<&Vec<Value>, RuntimeError> {
         match self {
             Array(arr) => Ok(arr),
-            _ => Err(message)
+            _ => Err(TypeMismatch(message.into()).into())
+        }
+    }
+
+    // booleans are true or false, while numbers are only true when they are not zero
+    fn expect_truthy(&self, message: &str) -> Result<bool, RuntimeError> {
+        match self {
+            Boolean(b) => Ok(*b),
+            _ => Ok(self.expect_real(message)? != 0.0)
+        }
+    }
+
+    // like expect_truthy, but the error says what the predicate of a conditional or loop turned out to be
+    fn expect_predicate(&self, construct: &str) -> Result<bool, RuntimeError> {
+        let found = match self {
+            Boolean(b) => return Ok(*b),
+            Number(c) if c.im == 0.0 => return Ok(c.re != 0.0),
+            Rational(n, _) => return Ok(*n != 0),
+            Number(_) => "a complex number",
+            _ => self.type_name()
+        };
+
+        Err(TypeMismatch(format!("a predicate to {} must be a boolean or a real number, not {}", construct, found)).into())
+    }
+
+    fn expect_function(&self, message: &str) -> Result<&str, RuntimeError> {
+        match self {
+            Function(name) => Ok(name),
+            _ => Err(TypeMismatch(message.into()).into())
+        }
+    }
+
+    // used for error messages that involve values of any type
+    fn type_name(&self) -> &'static str {
+        match self {
+            Number(_) => "a number",
+            Vector(_) => "a vector",
+            Array(_) => "an array",
+            Boolean(_) => "a boolean",
+            Function(_) => "a function",
+            Str(_) => "a string",
+            Matrix(_) => "a matrix",
+            Rational(_, _) => "a fraction"
+        }
+    }
+
+    // the name typeof gives back for this value
+    fn kind(&self) -> &'static str {
+        match self {
+            Number(_) => "number",
+            Vector(_) => "vector",
+            Array(_) => "array",
+            Boolean(_) => "boolean",
+            Function(_) => "function",
+            Str(_) => "string",
+            Matrix(_) => "matrix",
+            Rational(_, _) => "fraction"
+        }
+    }
+
+    // complex numbers have no ordering, so they can only be compared when both are real
+    fn ordered_pair(c: Complex64, c2: Complex64) -> Result<(f64, f64), RuntimeError> {
+        for num in [c, c2].iter() {
+            if num.im != 0.0 {
+                return Err(format!("cannot order {}, ordering is undefined for complex values", Number(*num)).into());
+            }
+        }
+
+        Ok((c.re, c2.re))
+    }
+
+    // collects values that must all be real numbers.
+    // complex values get their own error, since they cannot be ordered
+    fn ordered_reals(values: &[Value], message: &str) -> Result<Vec<f64>, RuntimeError> {
+        let mut reals = Vec::with_capacity(values.len());
+
+        for value in values {
+            if let Number(c) = value {
+                if c.im != 0.0 {
+                    return Err(format!("cannot order {}, ordering is undefined for complex values", value).into());
+                }
+            }
+
+            reals.push(value.expect_real(message)?);
+        }
+
+        Ok(reals)
+    }
+
+    // adds up values using the + operator, starting from the first value so
+    // that non-number values (such as vectors) can be summed too
+    fn sum(values: &[Value]) -> ValueOutput {
+        match values.split_first() {
+            Some((first, rest)) => rest.iter().try_fold(first.clone(), |sum, value| sum + value.clone()),
+            None => Ok(Value::real(0.0))
         }
     }
 
@@ -351,10 +848,13 @@ impl Value {
         let value_size = std::mem::size_of::<Value>();
 
         match self {
+            Vector(v) => value_size + v.len() * std::mem::size_of::<f64>(),
+            Str(string) => value_size + string.len(),
+            Matrix(rows) => value_size + rows.iter().map(|row| row.len() * std::mem::size_of::<Complex64>()).sum::<usize>(),
             Array(arr) => {
                 let mut size = value_size;
 
-                for value in arr {
+                for value in arr.iter() {
                     size += value.mem_size();
                 }
 
@@ -364,76 +864,181 @@ impl Value {
         }
     }
 
-    fn real(r: f64) -> Self {
+    fn abs(&self) -> ValueOutput {
+        match self {
+            Number(c) => Ok(Value::real(c.norm())),
+            Vector(v) => Ok(Value::real(magnitude(v))),
+            Array(arr) => Ok(Value::array(arr.iter().map(|value| value.abs()).collect::<Result<_, _>>()?)),
+            Rational(numerator, denominator) => Value::rational((*numerator as i128).abs(), *denominator as i128),
+            Boolean(_) | Function(_) | Matrix(_) | Str(_) => Err(format!("cannot find magnitude of {}", self.type_name()).into())
+        }
+    }
+
+    /// Creates an array of `values`.
+    ///
+    /// ```
+    /// use leibniz::Value;
+    ///
+    /// let array = Value::array(vec![Value::real(1.0), Value::real(2.0)]);
+    /// assert_eq!(array.expect_array("expected an array").unwrap().len(), 2);
+    /// ```
+    pub fn array(values: Vec<Value>) -> Self {
+        Array(Arc::new(values))
+    }
+
+    /// Creates a number with no imaginary part.
+    ///
+    /// ```
+    /// assert_eq!(leibniz::Value::real(3.0).to_string(), "3");
+    /// ```
+    pub fn real(r: f64) -> Self {
         Number(Complex64::new(r, 0.0))
     }
 
-    fn imaginary(i: f64) -> Self {
+    /// Creates a number with no real part.
+    ///
+    /// ```
+    /// assert_eq!(leibniz::Value::imaginary(2.0).to_string(), "2i");
+    /// ```
+    pub fn imaginary(i: f64) -> Self {
         Number(Complex64::new(0.0, i))
     }
 
-    fn push(mut self, val: Value) -> Value {
-        if let Array(ref mut arr) = self {
-            arr.push(val);
+    // a product for positive integers, which is exact up to 22!, and the gamma function of x + 1 for anything else
+    fn factorial(&self) -> ValueOutput {
+        let c = self.expect_complex("attempted to find factorial of non-number")?;
+
+        if c.im == 0.0 && c.re > 0.0 && c.re.fract() == 0.0 {
+            // 171! is already too big for a float, so there's no need to multiply all the way up
+            if c.re > MAX_FACTORIAL {
+                return Ok(Value::real(f64::INFINITY));
+            }
+
+            Ok(Value::real((2..=c.re as u64).map(|n| n as f64).product()))
+        } else if c.re == 0.0 && c.im == 0.0 {
+            Ok(Value::real(1.0))
+        } else {
+            Value::Number(c + 1.0).gamma()
         }
-        self
     }
 
     fn gamma(self) -> ValueOutput {
         const P: [f64; 8] = [
             676.5203681218851, -1259.1392167224028,
-            771.32342877765313, -176.61502916214059,
+            771.3234287776531, -176.6150291621406,
             12.507343278686905, -0.13857109526572012,
-            9.9843695780195716e-6, 1.5056327351493116e-7
+            9.984369578019572e-6, 1.5056327351493116e-7
         ];
 
         let pi = Complex64::new(std::f64::consts::PI, 0.0);
         let mut c = self.expect_complex("cannot calculate gamma for non-number")?;
 
+        // gamma has poles at zero and the negative integers, where the reflection formula would divide by a rounding error
+        if c.im == 0.0 && c.re <= 0.0 && c.re.fract() == 0.0 {
+            return Ok(Value::real(f64::NAN));
+        }
+
+        // the reflection formula, gamma(c) * gamma(1 - c) = pi / sin(pi * c), for where the approximation doesn't hold
         if c.re < 0.5 {
-            Ok(Value::Number(pi / (((pi * c).sin()) * (1.0 - c))).gamma()?)
+            let reflected = Value::Number(1.0 - c).gamma()?.expect_complex("cannot calculate gamma for non-number")?;
+            let divisor = (pi * c).sin() * reflected;
+
+            // complex division by an infinite reflection gives NaN, where dividing the real parts gives 0
+            if c.im == 0.0 {
+                Ok(Value::real(pi.re / divisor.re))
+            } else {
+                Ok(Value::Number(pi / divisor))
+            }
         } else {
-            let mut x = Complex64::new(0.99999999999980993, 0.0);
+            let mut x = Complex64::new(0.9999999999998099, 0.0);
             c -= 1.0;
 
-            for i in 0..P.len() {
-                x += P[i] / (c + (i as f64) + 1.0)
+            for (i, p) in P.iter().enumerate() {
+                x += p / (c + (i as f64) + 1.0)
             }
 
+            // worked out as a logarithm, since t to the power of c + 0.5 overflows long before gamma itself does
             let t = c + (P.len() as f64) - 0.5;
-            Ok(Value::Number((2.0 * pi).sqrt() * t.powc(c + 0.5) * (-t).exp() * x))
+            let ln_gamma = (2.0 * pi).sqrt().ln() + (c + 0.5) * t.ln() - t + x.ln();
+
+            // the imaginary part of a real result would be an infinity times zero once the real part overflows
+            if c.im == 0.0 {
+                Ok(Value::real(ln_gamma.re.exp()))
+            } else {
+                Ok(Value::Number(ln_gamma.exp()))
+            }
         }
     }
 }
 
+// a precision given to the formatter, like {:.3}, is the number of significant digits numbers are displayed with
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision();
+        let part = |x: f64| match precision {
+            Some(digits) => significant_digits(x, digits),
+            None => x
+        };
+        let nested = |value: &Value| match precision {
+            Some(digits) => format!("{:.*}", digits, value),
+            None => format!("{}", value)
+        };
+
         match self {
             Number(c) => {
-                if c.im == 0.0 {
-                    write!(f, "{}", c.re)
-                } else if c.re == 0.0 {
-                    if c.im == 1.0 {
+                let (re, im) = (part(c.re), part(c.im));
+
+                // a tiny part next to one that isn't is floating error, like in e^(i * pi), so it isn't shown.
+                // a number made only of tiny parts is shown as it is
+                let negligible = |x: f64, other: f64| x.abs() < DISPLAY_EPSILON && other.abs() >= DISPLAY_EPSILON;
+                let (re, im) = (
+                    if negligible(re, im) { 0.0 } else { re },
+                    if negligible(im, re) { 0.0 } else { im },
+                );
+
+                if im == 0.0 {
+                    write!(f, "{}", re)
+                } else if re == 0.0 {
+                    if im == 1.0 {
                         write!(f, "i")
                     } else {
-                        write!(f, "{}i", c.im)
+                        write!(f, "{}i", im)
                     }
                 } else {
-                    if c.im > 0.0 {
-                        write!(f, "{} + {}i", c.re, c.im)
+                    if im > 0.0 {
+                        write!(f, "{} + {}i", re, im)
                     } else {
-                        write!(f, "{} - {}i", c.re, c.im * -1.0)
+                        write!(f, "{} - {}i", re, -im)
                     }
                 }
             }
-            Vector(x, y) => write!(f, "({}, {})", x, y),
+            Vector(v) => {
+                let components = v.iter()
+                    .map(|x| format!("{}", part(*x)))
+                    .collect::<Vec<String>>();
+
+                write!(f, "({})", components.join(", "))
+            }
             Array(arr) => {
                 let elements = arr.iter()
-                    .map(|e| format!("{}", e))
+                    .map(nested)
                     .collect::<Vec<String>>();
 
                 write!(f, "[{}]", elements.join(", "))
             }
+            Boolean(b) => write!(f, "{}", b),
+            Function(name) => write!(f, "<function {}>", name),
+            Str(string) => write!(f, "{}", string),
+            Matrix(rows) => {
+                // each row goes on its own line, lined up under the opening bracket
+                let rows = rows.iter()
+                    .map(|row| row.iter().map(|c| nested(&Number(*c))).collect::<Vec<String>>().join(", "))
+                    .collect::<Vec<String>>();
+
+                write!(f, "[{}]", rows.join("\n "))
+            }
+            Rational(numerator, 1) => write!(f, "{}", numerator),
+            Rational(numerator, denominator) => write!(f, "{}/{}", numerator, denominator),
         }
     }
 }
@@ -442,43 +1047,401 @@ impl Clone for Value {
     fn clone(&self) -> Self {
         match self {
             Number(n) => Number(*n),
-            Vector(x, y) => Vector(*x, *y),
-            Array(arr) => Array(arr.clone())
+            Vector(v) => Vector(v.clone()),
+            Array(arr) => Array(arr.clone()),
+            Boolean(b) => Boolean(*b),
+            Function(name) => Function(name.clone()),
+            Str(string) => Str(string.clone()),
+            Matrix(rows) => Matrix(rows.clone()),
+            Rational(numerator, denominator) => Rational(*numerator, *denominator)
         }
     }
 }
 
-struct BuiltinFunction {
-    parameter_count: usize,
-    body: fn(&[Value], &RuntimeState) -> Result<Value, String>,
+// turns an index into a position within an array of the given length.
+// negative indices count backwards from the end of the array, so -1 is the last element
+fn resolve_index(index: f64, length: usize) -> Option<usize> {
+    if index.fract() != 0.0 {
+        return None;
+    }
+
+    let index = if index < 0.0 { length as f64 + index } else { index };
+
+    if index < 0.0 || index >= length as f64 {
+        None
+    } else {
+        Some(index as usize)
+    }
 }
 
-impl BuiltinFunction {
-    fn new(params: usize, closure: fn(&[Value], &RuntimeState) -> Result<Value, String>) -> Self {
-        BuiltinFunction {
-            parameter_count: params,
-            body: closure,
+// pushes the elements of arrays nested at any depth, in order
+// checks the element limit as it goes, since nested arrays that are shared can flatten into far more elements than they hold
+fn flatten(values: &[Value], flattened: &mut Vec<Value>, state: &RuntimeState) -> Result<(), RuntimeError> {
+    for value in values {
+        match value {
+            Array(arr) => flatten(arr, flattened, state)?,
+            _ => {
+                state.check_elements(flattened.len() as f64 + 1.0)?;
+                flattened.push(value.clone())
+            }
         }
     }
+
+    Ok(())
 }
 
-struct RuntimeState<'a> {
-    globals: HashMap<&'a str, Value>,
-    locals: HashMap<&'a str, Value>,
-    functions: HashMap<&'a str, &'a ParserNode<'a>>,
-    builtin_functions: HashMap<&'a str, BuiltinFunction>,
-    in_function: bool,
-    start_instant: Instant
+// the euclidean length of a vector with the given components
+fn magnitude(components: &[f64]) -> f64 {
+    components.iter().map(|x| x * x).sum::<f64>().sqrt()
 }
 
-impl<'a> RuntimeState<'a> {
-    fn new() -> Self {
-        RuntimeState {
-            globals: HashMap::new(),
-            locals: HashMap::new(),
-            functions: HashMap::new(),
-            builtin_functions: HashMap::new(),
+// the determinant of a square matrix, found through gaussian elimination with partial pivoting
+fn determinant(rows: &[Vec<Complex64>]) -> Complex64 {
+    let mut rows = rows.to_vec();
+    let mut det = Complex64::new(1.0, 0.0);
+
+    for i in 0..rows.len() {
+        let pivot = (i..rows.len()).max_by(|&a, &b| rows[a][i].norm().total_cmp(&rows[b][i].norm())).unwrap();
+
+        if rows[pivot][i].norm() == 0.0 {
+            return Complex64::new(0.0, 0.0);
+        }
+
+        if pivot != i {
+            rows.swap(pivot, i);
+            det = -det;
+        }
+
+        det *= rows[i][i];
+
+        let (top, bottom) = rows.split_at_mut(i + 1);
+        let pivot_row = &top[i];
+
+        for row in bottom {
+            let factor = row[i] / pivot_row[i];
+
+            for (element, pivot_element) in row.iter_mut().zip(pivot_row).skip(i) {
+                *element -= factor * pivot_element;
+            }
+        }
+    }
+
+    det
+}
+
+// the inverse of a square matrix found through gauss-jordan elimination, or none if the matrix is singular
+fn inverse(rows: &[Vec<Complex64>]) -> Option<Vec<Vec<Complex64>>> {
+    let n = rows.len();
+    let mut rows = rows.to_vec();
+    let mut inverse = (0..n)
+        .map(|i| (0..n).map(|j| Complex64::new(if i == j { 1.0 } else { 0.0 }, 0.0)).collect())
+        .collect::<Vec<Vec<Complex64>>>();
+
+    for i in 0..n {
+        let pivot = (i..n).max_by(|&a, &b| rows[a][i].norm().total_cmp(&rows[b][i].norm())).unwrap();
+
+        if rows[pivot][i].norm() == 0.0 {
+            return None;
+        }
+
+        rows.swap(pivot, i);
+        inverse.swap(pivot, i);
+
+        let divisor = rows[i][i];
+
+        for k in 0..n {
+            rows[i][k] /= divisor;
+            inverse[i][k] /= divisor;
+        }
+
+        for j in 0..n {
+            if j == i {
+                continue;
+            }
+
+            let factor = rows[j][i];
+
+            for k in 0..n {
+                let subtracted = factor * rows[i][k];
+                rows[j][k] -= subtracted;
+
+                let subtracted = factor * inverse[i][k];
+                inverse[j][k] -= subtracted;
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+// the real numbers in a non-empty array, for the statistics builtins
+fn statistics_reals(value: &Value, statistic: &str) -> Result<Vec<f64>, RuntimeError> {
+    let message = format!("expected an array of real numbers to find {} of", statistic);
+    let array = value.expect_array(&message)?;
+
+    if array.is_empty() {
+        return Err(format!("cannot find {} of an empty array", statistic).into());
+    }
+
+    array.iter().map(|value| value.expect_real(&message)).collect()
+}
+
+// the population variance, the mean of the squared distances from the mean
+fn variance(reals: &[f64]) -> f64 {
+    let mean = reals.iter().sum::<f64>() / reals.len() as f64;
+    reals.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / reals.len() as f64
+}
+
+// an operation between two fractions as numerators and denominators, which may not be in lowest terms.
+// None when the result isn't a fraction, or is too big to work out
+fn fraction_operation(operator: Operator, (a, b): (i128, i128), (c, d): (i128, i128)) -> Option<(i128, i128)> {
+    match operator {
+        Operator::Add => Some(((a * d).checked_add(c * b)?, b * d)),
+        Operator::Subtract => Some(((a * d).checked_sub(c * b)?, b * d)),
+        Operator::Multiply => Some((a * c, b * d)),
+        // dividing by zero falls back to floats, which give inf or NaN
+        Operator::Divide if c != 0 => Some((a * d, b * c)),
+        Operator::Modulo => Some(((a * d).checked_rem(c * b)?, b * d)),
+        // only integer powers of a fraction are still fractions
+        Operator::Power if d == 1 && (a != 0 || c >= 0) => {
+            let exponent = u32::try_from(c.abs()).ok()?;
+            let (numerator, denominator) = (a.checked_pow(exponent)?, b.checked_pow(exponent)?);
+
+            Some(if c < 0 { (denominator, numerator) } else { (numerator, denominator) })
+        }
+        _ => None
+    }
+}
+
+// the remainder of a / b, which unlike % takes the sign of the divisor
+fn floored_mod(a: f64, b: f64) -> f64 {
+    ((a % b) + b) % b
+}
+
+// how small a part of a complex number can be before it is displayed as zero, as long as the other part is bigger
+const DISPLAY_EPSILON: f64 = 1e-14;
+
+// rounds a number to the given number of significant digits, for displaying it
+fn significant_digits(x: f64, digits: usize) -> f64 {
+    if x == 0.0 || !x.is_finite() || digits == 0 {
+        return x;
+    }
+
+    let magnitude = x.abs().log10().floor() as i32;
+    let scale = 10f64.powi(digits.min(i32::MAX as usize) as i32 - 1 - magnitude);
+
+    if scale.is_infinite() || scale == 0.0 {
+        x
+    } else {
+        (x * scale).round() / scale
+    }
+}
+
+// the greatest common divisor by the euclidean algorithm, which is never negative
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+// n and r for choose and perm, which must be integers with 0 <= r <= n
+fn combinatorics_arguments(params: &[Value], signature: &str) -> Result<(f64, f64), RuntimeError> {
+    let message = format!("expected a non-negative integer in {}", signature);
+    let n = params[0].expect_integer(&message)?;
+    let r = params[1].expect_integer(&message)?;
+
+    if n < 0 || r < 0 {
+        Err(TypeMismatch(message).into())
+    } else if r > n {
+        Err(format!("cannot pick {} out of {} in {}", r, n, signature).into())
+    } else {
+        Ok((n as f64, r as f64))
+    }
+}
+
+// the product of factor(i) for i from 1 to count. the factors are never less than 1, so it stops early once it's infinite
+fn product_of_factors(count: f64, factor: impl Fn(f64) -> f64) -> f64 {
+    let mut product = 1f64;
+    let mut i = 1.0;
+
+    while i <= count && product.is_finite() {
+        product *= factor(i);
+        i += 1.0;
+    }
+
+    product
+}
+
+// rounds x to a multiple of 1 / scale. once x * scale is too big to have a fractional part, x already has
+// no more digits than that to round off, and scaling it again could overflow or lose precision, so it's left as is
+fn round_to_scale(x: f64, scale: f64) -> f64 {
+    let scaled = x * scale;
+
+    if !scaled.is_finite() || scaled.abs() >= MAX_EXACT_INTEGER {
+        x
+    } else {
+        scaled.round() / scale
+    }
+}
+
+// 2^53, past which every float is an integer
+const MAX_EXACT_INTEGER: f64 = 9007199254740992.0;
+
+// the largest integer whose factorial fits in a float
+const MAX_FACTORIAL: f64 = 170.0;
+
+// how close to the second bound of a range, relative to the step, a step has to land to count as reaching it
+const RANGE_EPSILON: f64 = 1e-9;
+
+// the values a range steps through from first to second, in the same way loops do.
+// both bounds are included, and the last step is cut short so it never oversteps the second bound.
+// a step that lands a hair short of the second bound from floating error lands on it instead, so it isn't included twice
+fn range_values(first: f64, second: f64, step: f64) -> impl Iterator<Item = f64> {
+    let step = if first < second { step.abs() } else { -step.abs() };
+    let mut next = Some(first);
+
+    std::iter::from_fn(move || {
+        let x = next?;
+        let stepped = x + step;
+
+        next = if x == second {
+            None
+        } else if (step > 0.0 && stepped > second) || (step < 0.0 && stepped < second) || (second - stepped).abs() < step.abs() * RANGE_EPSILON {
+            Some(second)
+        } else {
+            Some(stepped)
+        };
+
+        Some(x)
+    })
+}
+
+// a range has to have finite bounds and a finite, non-zero step, otherwise it would never reach its second bound
+fn check_range(first: f64, second: f64, step: f64) -> Result<(), RuntimeError> {
+    if !first.is_finite() || !second.is_finite() {
+        return Err("the bounds of a range must be finite".into());
+    }
+
+    if !step.is_finite() {
+        return Err("the step of a range must be finite".into());
+    }
+
+    if step == 0.0 {
+        return Err("a step cannot be 0".into());
+    }
+
+    Ok(())
+}
+
+// the body of a builtin function. it is shared so host programs can register closures that capture their own state
+type BuiltinBody = Arc<dyn Fn(&[Value], &mut RuntimeState) -> Result<Value, RuntimeError> + Send + Sync>;
+
+#[derive(Clone)]
+struct BuiltinFunction {
+    parameter_count: Option<usize>, // None for functions accepting any number of parameters
+    body: BuiltinBody,
+}
+
+impl BuiltinFunction {
+    fn new(
+        params: usize,
+        closure: impl Fn(&[Value], &mut RuntimeState) -> Result<Value, RuntimeError> + Send + Sync + 'static,
+    ) -> Self {
+        BuiltinFunction {
+            parameter_count: Some(params),
+            body: Arc::new(closure),
+        }
+    }
+
+    fn variadic(closure: impl Fn(&[Value], &mut RuntimeState) -> Result<Value, RuntimeError> + Send + Sync + 'static) -> Self {
+        BuiltinFunction {
+            parameter_count: None,
+            body: Arc::new(closure),
+        }
+    }
+}
+
+pub struct RuntimeState<'a> {
+    globals: HashMap<&'a str, Value>,
+    locals: HashMap<&'a str, Value>,
+    functions: HashMap<&'a str, &'a ParserNode<'a>>,
+    builtin_functions: HashMap<&'a str, BuiltinFunction>,
+    in_function: bool,
+    depth: usize, // how many declared function calls are currently being evaluated
+    steps: usize, // how many nodes have been evaluated so far
+    limits: Limits,
+    captured_output: Option<String>, // when set, printed lines are collected here instead of going to stdout
+    precision: Option<usize>, // the significant digits print and format show numbers with, or None for all of them
+    start_instant: Instant
+}
+
+const DEFAULT_RECURSION_LIMIT: usize = 1000;
+
+// the stack a function call is given room for. a call to a one line function was measured to take about 64 KiB
+// in a debug build and 3 KiB in a release build, with each level of nesting in its body adding about a quarter
+// of that, so this leaves room for bodies around a dozen levels deep
+const STACK_PER_CALL: usize = if cfg!(debug_assertions) { 256 * 1024 } else { 16 * 1024 };
+const DEFAULT_STACK_SIZE: usize = DEFAULT_RECURSION_LIMIT * STACK_PER_CALL;
+const DEFAULT_ELEMENT_LIMIT: usize = 10_000_000;
+
+/// Limits on how much a script can do before it is stopped, for running scripts that cannot be trusted.
+///
+/// ```
+/// use leibniz::{ErrorKind, Limits, Runtime};
+///
+/// let mut runtime = Runtime::new();
+/// runtime.limits = Limits { stack_size: None, recursion: 100, ..Limits::default() };
+///
+/// let root = leibniz::parser::parse_leibniz_file("let f(x) = f(x + 1)\nf(0)").unwrap();
+/// let error = runtime.execute(root).unwrap_err();
+/// assert_eq!(error.kind, ErrorKind::RecursionLimitExceeded(100));
+/// ```
+#[derive(Clone, Copy)]
+pub struct Limits {
+    pub recursion: usize,     // the maximum depth of nested function calls
+    pub steps: Option<usize>, // the maximum number of nodes that can be evaluated, or None for no limit
+    pub timeout: Option<Duration>, // the longest the script can run for, or None for no limit
+    // the most elements a builtin or a collect loop can make an array or matrix with, or None for no limit.
+    // builtins like map and zip that make no more elements than their arguments already have are not checked,
+    // and neither are operators, so multiplying a column matrix by a row matrix can still make a large one
+    pub elements: Option<usize>,
+    // the size of the stack of the thread the script runs on, or None to run it on the calling thread instead.
+    // the default is large enough for the default recursion limit to be reached before the stack runs out.
+    // None is only safe along with a recursion limit that fits the stack of the calling thread, since running
+    // out of it aborts the host. the default limit of 1000 does not fit the usual 2 to 8 MiB, while a limit of
+    // about 20 calls is safe in a debug build, and a few hundred in a release build
+    pub stack_size: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            recursion: DEFAULT_RECURSION_LIMIT,
+            steps: None,
+            timeout: None,
+            elements: Some(DEFAULT_ELEMENT_LIMIT),
+            stack_size: Some(DEFAULT_STACK_SIZE),
+        }
+    }
+}
+
+impl<'a> RuntimeState<'a> {
+    fn new(limits: Limits) -> Self {
+        RuntimeState {
+            globals: HashMap::new(),
+            locals: HashMap::new(),
+            functions: HashMap::new(),
+            builtin_functions: HashMap::new(),
             in_function: false,
+            depth: 0,
+            steps: 0,
+            limits,
+            captured_output: None,
+            precision: None,
             start_instant: Instant::now() // this will be set later
         }
     }
@@ -486,13 +1449,24 @@ impl<'a> RuntimeState<'a> {
     fn add_default_globals_and_functions(&mut self) {
         self.add_global("pi", Value::real(std::f64::consts::PI));
         self.add_global("e", Value::real(std::f64::consts::E));
+        self.add_global("tau", Value::real(std::f64::consts::TAU));
+        self.add_global("phi", Value::real((1.0 + 5f64.sqrt()) / 2.0));
+        self.add_global("inf", Value::real(f64::INFINITY));
+        self.add_global("true", Boolean(true));
+        self.add_global("false", Boolean(false));
 
         self.add_builtin(
             "vec",
-            BuiltinFunction::new(2, |params, _| {
-                let x = params[0].expect_real("the x component of the vector is not a number")?;
-                let y = params[1].expect_real("the y component of the vector is not a number")?;
-                Ok(Vector(x, y))
+            BuiltinFunction::variadic(|params, _| {
+                if params.len() < 2 {
+                    return Err(format!("a vector needs at least 2 components, got {}", params.len()).into());
+                }
+
+                let components = params.iter()
+                    .map(|param| param.expect_real("the components of a vector must be real numbers"))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Vector(components))
             }),
         );
 
@@ -500,7 +1474,7 @@ impl<'a> RuntimeState<'a> {
             "x",
             BuiltinFunction::new(1, |params, _| {
                 let vec = params[0].expect_vector("expected vector to take x component out of")?;
-                Ok(Value::real(vec.0))
+                Ok(Value::real(vec[0]))
             }),
         );
 
@@ -508,7 +1482,231 @@ impl<'a> RuntimeState<'a> {
             "y",
             BuiltinFunction::new(1, |params, _| {
                 let vec = params[0].expect_vector("expected vector to take y component out of")?;
-                Ok(Value::real(vec.1))
+                Ok(Value::real(vec[1]))
+            }),
+        );
+
+        self.add_builtin(
+            "z",
+            BuiltinFunction::new(1, |params, _| {
+                let vec = params[0].expect_vector("expected vector to take z component out of")?;
+
+                match vec.get(2) {
+                    Some(z) => Ok(Value::real(*z)),
+                    None => Err("cannot take the z component of a 2d vector".into())
+                }
+            }),
+        );
+
+        self.add_builtin(
+            "dot",
+            BuiltinFunction::new(2, |params, _| {
+                // arrays of numbers of any length work too, as long as both are the same length
+                if let (Array(a), Array(b)) = (&params[0], &params[1]) {
+                    if a.len() != b.len() {
+                        return Err(format!("cannot find dot product of arrays of different lengths {} and {}", a.len(), b.len()).into());
+                    }
+
+                    let mut product = Complex64::new(0.0, 0.0);
+
+                    for (x, y) in a.iter().zip(b.iter()) {
+                        product += x.expect_complex("expected arrays of numbers in dot(a, b)")?
+                            * y.expect_complex("expected arrays of numbers in dot(a, b)")?;
+                    }
+
+                    return Ok(Number(product));
+                }
+
+                let a = params[0].expect_vector("expected vector as first argument of dot(a, b)")?;
+                let b = params[1].expect_vector("expected vector as second argument of dot(a, b)")?;
+
+                if a.len() != b.len() {
+                    return Err(format!("cannot find dot product of vectors of different dimensions {} and {}", a.len(), b.len()).into());
+                }
+
+                Ok(Value::real(a.iter().zip(b).map(|(x, y)| x * y).sum()))
+            }),
+        );
+
+        self.add_builtin(
+            "cross",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_vector("expected vector as first argument of cross(a, b)")?;
+                let b = params[1].expect_vector("expected vector as second argument of cross(a, b)")?;
+
+                match (a.len(), b.len()) {
+                    // the cross product of 2d vectors is the z component of their 3d cross product
+                    (2, 2) => Ok(Value::real(a[0] * b[1] - a[1] * b[0])),
+                    (3, 3) => Ok(Vector(vec![
+                        a[1] * b[2] - a[2] * b[1],
+                        a[2] * b[0] - a[0] * b[2],
+                        a[0] * b[1] - a[1] * b[0]
+                    ])),
+                    _ => Err(format!("cannot find cross product of vectors with {} and {} components, only 2d and 3d vectors are supported", a.len(), b.len()).into())
+                }
+            }),
+        );
+
+        self.add_builtin(
+            "magnitude",
+            BuiltinFunction::new(1, |params, _| {
+                let v = params[0].expect_vector("expected vector to find magnitude of")?;
+                Ok(Value::real(magnitude(v)))
+            }),
+        );
+
+        self.add_builtin(
+            "normalize",
+            BuiltinFunction::new(1, |params, _| {
+                let v = params[0].expect_vector("expected vector to normalize")?;
+                let length = magnitude(v);
+
+                if length == 0.0 {
+                    return Err("cannot normalize the zero vector".into());
+                }
+
+                Ok(Vector(v.iter().map(|x| x / length).collect()))
+            }),
+        );
+
+        self.add_builtin(
+            "distance",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_vector("expected vector as first argument of distance(a, b)")?;
+                let b = params[1].expect_vector("expected vector as second argument of distance(a, b)")?;
+
+                if a.len() != b.len() {
+                    return Err(format!("cannot find distance between vectors of different dimensions {} and {}", a.len(), b.len()).into());
+                }
+
+                let difference = a.iter().zip(b).map(|(x, y)| x - y).collect::<Vec<f64>>();
+                Ok(Value::real(magnitude(&difference)))
+            }),
+        );
+
+        self.add_builtin(
+            "angle",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_vector("expected vector as first argument of angle(a, b)")?;
+                let b = params[1].expect_vector("expected vector as second argument of angle(a, b)")?;
+
+                if a.len() != b.len() {
+                    return Err(format!("cannot find angle between vectors of different dimensions {} and {}", a.len(), b.len()).into());
+                }
+
+                let lengths = magnitude(a) * magnitude(b);
+
+                if lengths == 0.0 {
+                    return Err("cannot find angle involving the zero vector".into());
+                }
+
+                // rounding can push the cosine slightly outside of [-1, 1] for parallel vectors
+                let cosine = a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>() / lengths;
+                Ok(Value::real(cosine.clamp(-1.0, 1.0).acos()))
+            }),
+        );
+
+        self.add_builtin(
+            "rotate",
+            BuiltinFunction::new(2, |params, _| {
+                let v = params[0].expect_vector("expected vector as first argument of rotate(v, theta)")?;
+                let theta = params[1].expect_real("expected real angle as second argument of rotate(v, theta)")?;
+
+                if v.len() != 2 {
+                    return Err(format!("cannot rotate a vector with {} components, only 2d vectors can be rotated", v.len()).into());
+                }
+
+                let (s, c) = theta.sin_cos();
+                Ok(Vector(vec![v[0] * c - v[1] * s, v[0] * s + v[1] * c]))
+            }),
+        );
+
+        self.add_builtin(
+            "matrix",
+            BuiltinFunction::new(1, |params, _| {
+                let arr = params[0].expect_array("expected an array of rows to create a matrix from")?;
+
+                if arr.is_empty() {
+                    return Err("a matrix needs at least one row".into());
+                }
+
+                let rows = arr.iter()
+                    .map(|row| {
+                        let row = row.expect_array("expected each row of a matrix to be an array")?;
+                        row.iter().map(|element| element.expect_complex("expected each element of a matrix to be a number")).collect()
+                    })
+                    .collect::<Result<Vec<Vec<Complex64>>, RuntimeError>>()?;
+
+                let columns = rows[0].len();
+
+                if columns == 0 {
+                    return Err("a matrix needs at least one column".into());
+                }
+
+                if let Some(row) = rows.iter().find(|row| row.len() != columns) {
+                    return Err(format!("cannot create a matrix from rows of different lengths {} and {}", columns, row.len()).into());
+                }
+
+                Ok(Matrix(rows))
+            }),
+        );
+
+        self.add_builtin(
+            "identity",
+            BuiltinFunction::new(1, |params, state| {
+                let n = params[0].expect_dimension("expected a positive integer size in identity(n)")?;
+                state.check_elements(n as f64 * n as f64)?;
+
+                Ok(Matrix((0..n).map(|i| {
+                    (0..n).map(|j| Complex64::new(if i == j { 1.0 } else { 0.0 }, 0.0)).collect()
+                }).collect()))
+            }),
+        );
+
+        self.add_builtin(
+            "zeros",
+            BuiltinFunction::new(2, |params, state| {
+                let rows = params[0].expect_dimension("expected a positive integer number of rows in zeros(rows, cols)")?;
+                let columns = params[1].expect_dimension("expected a positive integer number of columns in zeros(rows, cols)")?;
+                state.check_elements(rows as f64 * columns as f64)?;
+                Ok(Matrix(vec![vec![Complex64::new(0.0, 0.0); columns]; rows]))
+            }),
+        );
+
+        self.add_builtin(
+            "transpose",
+            BuiltinFunction::new(1, |params, _| {
+                let rows = params[0].expect_matrix("expected matrix to transpose")?;
+                Ok(Matrix((0..rows[0].len()).map(|j| rows.iter().map(|row| row[j]).collect()).collect()))
+            }),
+        );
+
+        self.add_builtin(
+            "det",
+            BuiltinFunction::new(1, |params, _| {
+                let rows = params[0].expect_matrix("expected matrix to find determinant of")?;
+
+                if rows.len() != rows[0].len() {
+                    return Err(format!("cannot find determinant of a {} matrix, it is not square", Value::shape(rows)).into());
+                }
+
+                Ok(Number(determinant(rows)))
+            }),
+        );
+
+        self.add_builtin(
+            "inverse",
+            BuiltinFunction::new(1, |params, _| {
+                let rows = params[0].expect_matrix("expected matrix to find inverse of")?;
+
+                if rows.len() != rows[0].len() {
+                    return Err(format!("cannot find inverse of a {} matrix, it is not square", Value::shape(rows)).into());
+                }
+
+                match inverse(rows) {
+                    Some(inverse) => Ok(Matrix(inverse)),
+                    None => Err("cannot find inverse, matrix is singular".into())
+                }
             }),
         );
 
@@ -537,105 +1735,1068 @@ impl<'a> RuntimeState<'a> {
         );
 
         self.add_builtin(
-            "log",
-            BuiltinFunction::new(1, |params, _| {
-                let num = params[0].expect_complex("expected number to find logarithm of")?;
-                Ok(Number(num.log(10.0)))
-            }),
+            "asin",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find inverse sine of")?;
+                Ok(Number(num.asin()))
+            }),
+        );
+
+        self.add_builtin(
+            "acos",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find inverse cosine of")?;
+                Ok(Number(num.acos()))
+            }),
+        );
+
+        self.add_builtin(
+            "atan",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find inverse tangent of")?;
+                Ok(Number(num.atan()))
+            }),
+        );
+
+        self.add_builtin(
+            "sinh",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find hyperbolic sine of")?;
+                Ok(Number(num.sinh()))
+            }),
+        );
+
+        self.add_builtin(
+            "cosh",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find hyperbolic cosine of")?;
+                Ok(Number(num.cosh()))
+            }),
+        );
+
+        self.add_builtin(
+            "tanh",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find hyperbolic tangent of")?;
+                Ok(Number(num.tanh()))
+            }),
+        );
+
+        self.add_builtin(
+            "asinh",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find inverse hyperbolic sine of")?;
+                Ok(Number(num.asinh()))
+            }),
+        );
+
+        self.add_builtin(
+            "acosh",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find inverse hyperbolic cosine of")?;
+                Ok(Number(num.acosh()))
+            }),
+        );
+
+        self.add_builtin(
+            "atanh",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find inverse hyperbolic tangent of")?;
+                Ok(Number(num.atanh()))
+            }),
+        );
+
+        self.add_builtin(
+            "atan2",
+            BuiltinFunction::new(2, |params, _| {
+                let y = params[0].expect_real("expected a real y coordinate in atan2(y, x)")?;
+                let x = params[1].expect_real("expected a real x coordinate in atan2(y, x)")?;
+                Ok(Value::real(y.atan2(x)))
+            }),
+        );
+
+        self.add_builtin(
+            "rad",
+            BuiltinFunction::new(1, |params, _| {
+                let degrees = params[0].expect_real("expected a real angle in degrees to convert to radians")?;
+                Ok(Value::real(degrees.to_radians()))
+            }),
+        );
+
+        self.add_builtin(
+            "deg",
+            BuiltinFunction::new(1, |params, _| {
+                let radians = params[0].expect_real("expected a real angle in radians to convert to degrees")?;
+                Ok(Value::real(radians.to_degrees()))
+            }),
+        );
+
+        self.add_builtin(
+            "log",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected number to find logarithm of")?;
+                Ok(Number(num.log(10.0)))
+            }),
+        );
+
+        self.add_builtin(
+            "logn",
+            BuiltinFunction::new(2, |params, _| {
+                let n = params[0].expect_real("expected real base to logarithm")?;
+                let x = params[1].expect_complex("expected number to find logarithm of")?;
+                Ok(Number(x.log(n)))
+            }),
+        );
+
+        self.add_builtin(
+            "ln",
+            BuiltinFunction::new(1, |params, _| {
+                let num =
+                    params[0].expect_complex("expected number to find natural logarithm of")?;
+                Ok(Number(num.ln()))
+            }),
+        );
+
+        self.add_builtin(
+            "exp",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a number to find the exponential of")?;
+                Ok(Number(num.exp()))
+            }),
+        );
+
+        self.add_builtin(
+            "sqrt",
+            BuiltinFunction::new(1, |params, _| {
+                if let Vector(v) = &params[0] {
+                    return Ok(Vector(v.iter().map(|x| x.sqrt()).collect()));
+                }
+
+                let num = params[0].expect_complex("expected a number or vector to find square root of")?;
+                Ok(Number(num.sqrt()))
+            }),
+        );
+
+        self.add_builtin(
+            "pow",
+            BuiltinFunction::new(2, |params, _| {
+                params[0].clone().pow(params[1].clone())
+            }),
+        );
+
+        self.add_builtin(
+            "roots",
+            BuiltinFunction::new(2, |params, _| {
+                let z = params[0].expect_complex("expected a number to find the roots of in roots(z, n)")?;
+                let n = params[1].expect_dimension("expected a positive integer n in roots(z, n)")?;
+
+                if n == 1 {
+                    return Ok(Value::array(vec![Number(z)]));
+                }
+
+                // the principal root, rotated around by a full turn divided into n
+                let (r, theta) = z.to_polar();
+                let modulus = r.powf(1.0 / n as f64);
+
+                Ok(Value::array((0..n)
+                    .map(|k| Number(Complex64::from_polar(modulus, (theta + 2.0 * std::f64::consts::PI * k as f64) / n as f64)))
+                    .collect()))
+            }),
+        );
+
+        self.add_builtin(
+            "abs",
+            BuiltinFunction::new(1, |params, _| {
+                params[0].abs()
+            }),
+        );
+
+        self.add_builtin(
+            "sign",
+            BuiltinFunction::new(1, |params, _| {
+                let x = params[0].expect_real("expected a real number to find sign of")?;
+
+                // checked against 0 first, since -0 has its sign bit set
+                if x == 0.0 {
+                    Ok(Value::real(0.0))
+                } else {
+                    Ok(Value::real(x.signum()))
+                }
+            }),
+        );
+
+        self.add_builtin(
+            "isnan",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a number in isnan(x)")?;
+                Ok(Boolean(num.re.is_nan() || num.im.is_nan()))
+            }),
+        );
+
+        self.add_builtin(
+            "isinf",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a number in isinf(x)")?;
+                Ok(Boolean(num.re.is_infinite() || num.im.is_infinite()))
+            }),
+        );
+
+        self.add_builtin(
+            "isfinite",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a number in isfinite(x)")?;
+                Ok(Boolean(num.re.is_finite() && num.im.is_finite()))
+            }),
+        );
+
+        self.add_builtin(
+            "floor",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a number to find floor of")?;
+                Ok(Number(Complex64::new(num.re.floor(), num.im.floor())))
+            }),
+        );
+
+        self.add_builtin(
+            "ceil",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a number to find ceiling of")?;
+                Ok(Number(Complex64::new(num.re.ceil(), num.im.ceil())))
+            }),
+        );
+
+        self.add_builtin(
+            "round",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a number to round")?;
+                Ok(Number(Complex64::new(num.re.round(), num.im.round())))
+            }),
+        );
+
+        self.add_builtin(
+            "trunc",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a number to truncate")?;
+                Ok(Number(Complex64::new(num.re.trunc(), num.im.trunc())))
+            }),
+        );
+
+        self.add_builtin(
+            "round_to",
+            BuiltinFunction::new(2, |params, _| {
+                let num = params[0].expect_complex("expected a number to round in round_to(x, digits)")?;
+                let digits = params[1].expect_count("expected a non-negative integer number of digits in round_to(x, digits)")?;
+                let scale = 10f64.powi(digits.min(i32::MAX as usize) as i32);
+
+                Ok(Number(Complex64::new(round_to_scale(num.re, scale), round_to_scale(num.im, scale))))
+            }),
+        );
+
+        self.add_builtin(
+            "mod",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_real("expected a real dividend in mod(a, b)")?;
+                let b = params[1].expect_real("expected a real divisor in mod(a, b)")?;
+
+                if b == 0.0 {
+                    return Err(DivisionByZero.into());
+                }
+
+                Ok(Value::real(floored_mod(a, b)))
+            }),
+        );
+
+        self.add_builtin(
+            "divmod",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_real("expected a real dividend in divmod(a, b)")?;
+                let b = params[1].expect_real("expected a real divisor in divmod(a, b)")?;
+
+                if b == 0.0 {
+                    return Err(DivisionByZero.into());
+                }
+
+                // the quotient is worked out from the remainder mod gives, so the two always agree,
+                // even where rounding would have floored a / b to the integer below. adding 0 turns -0 into 0
+                let remainder = floored_mod(a, b);
+                let quotient = ((a - remainder) / b).round() + 0.0;
+
+                Ok(Value::array(vec![Value::real(quotient), Value::real(remainder)]))
+            }),
+        );
+
+        self.add_builtin(
+            "frac",
+            BuiltinFunction::new(2, |params, _| {
+                let numerator = params[0].expect_integer("expected an integer numerator in frac(numerator, denominator)")?;
+                let denominator = params[1].expect_integer("expected an integer denominator in frac(numerator, denominator)")?;
+
+                Value::rational(numerator as i128, denominator as i128)
+            }),
+        );
+
+        self.add_builtin(
+            "gcd",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_integer("expected an integer in gcd(a, b)")?;
+                let b = params[1].expect_integer("expected an integer in gcd(a, b)")?;
+
+                Ok(Value::real(gcd(a as i128, b as i128) as f64))
+            }),
+        );
+
+        self.add_builtin(
+            "lcm",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_integer("expected an integer in lcm(a, b)")?;
+                let b = params[1].expect_integer("expected an integer in lcm(a, b)")?;
+
+                if a == 0 || b == 0 {
+                    return Ok(Value::real(0.0));
+                }
+
+                let (a, b) = (a as i128, b as i128);
+                Ok(Value::real((a / gcd(a, b) * b).abs() as f64))
+            }),
+        );
+
+        self.add_builtin(
+            "gamma",
+            BuiltinFunction::new(1, |params, _| {
+                let c = params[0].expect_complex("expected a number to find gamma of")?;
+
+                // positive integers go through the factorial, which is exact where the approximation is a little off,
+                // and is infinite rather than overflowing once the result is too big for a float
+                if c.im == 0.0 && c.re >= 1.0 && c.re.fract() == 0.0 {
+                    Value::real(c.re - 1.0).factorial()
+                } else {
+                    Value::Number(c).gamma()
+                }
+            }),
+        );
+
+        self.add_builtin(
+            "factorial",
+            BuiltinFunction::new(1, |params, _| {
+                params[0].factorial()
+            }),
+        );
+
+        self.add_builtin(
+            "choose",
+            BuiltinFunction::new(2, |params, _| {
+                let (n, r) = combinatorics_arguments(params, "choose(n, r)")?;
+
+                // picking r is the same as leaving out n - r, and the fewer factors the better
+                let r = r.min(n - r);
+                Ok(Value::real(product_of_factors(r, |i| (n - r + i) / i).round()))
+            }),
+        );
+
+        self.add_builtin(
+            "perm",
+            BuiltinFunction::new(2, |params, _| {
+                let (n, r) = combinatorics_arguments(params, "perm(n, r)")?;
+                Ok(Value::real(product_of_factors(r, |i| n - r + i)))
+            }),
+        );
+
+        self.add_builtin(
+            "print",
+            BuiltinFunction::new(1, |params, state| {
+                let line = state.display(&params[0]);
+                state.print_line(&line);
+                Ok(params[0].clone())
+            }),
+        );
+
+        self.add_builtin(
+            "assert",
+            BuiltinFunction::new(2, |params, _| {
+                let condition = params[0].expect_truthy("expected a boolean or real number as the condition in assert(condition, message)")?;
+                let message = params[1].expect_string("expected a string as the message in assert(condition, message)")?;
+
+                if condition {
+                    Ok(params[0].clone())
+                } else {
+                    Err(format!("assertion failed: {}", message).into())
+                }
+            }),
+        );
+
+        self.add_builtin(
+            "error",
+            BuiltinFunction::new(1, |params, _| {
+                let message = params[0].expect_string("expected a string as the message in error(message)")?;
+                Err(message.into())
+            }),
+        );
+
+        self.add_builtin(
+            "set_precision",
+            BuiltinFunction::new(1, |params, state| {
+                let digits = params[0].expect_count("expected a non-negative integer number of digits in set_precision(digits)")?;
+
+                // 0 goes back to showing every digit
+                state.precision = if digits == 0 { None } else { Some(digits) };
+                Ok(Value::real(digits as f64))
+            }),
+        );
+
+        self.add_builtin(
+            "format",
+            BuiltinFunction::variadic(|params, state| {
+                if params.is_empty() {
+                    return Err("expected a template string in format(template, ...)".into());
+                }
+
+                let template = params[0].expect_string("expected a template string in format(template, ...)")?;
+                let mut arguments = params[1..].iter();
+                let mut placeholders = 0;
+                let mut result = String::new();
+                let mut chars = template.chars().peekable();
+
+                while let Some(c) = chars.next() {
+                    match (c, chars.peek()) {
+                        ('{', Some('{')) | ('}', Some('}')) => {
+                            result.push(c);
+                            chars.next();
+                        }
+                        ('{', Some('}')) => {
+                            chars.next();
+                            placeholders += 1;
+
+                            if let Some(argument) = arguments.next() {
+                                result += &state.display(argument);
+                            }
+                        }
+                        ('{', _) => return Err("unmatched { in format string, use {{ for a literal {".into()),
+                        ('}', _) => return Err("unmatched } in format string, use }} for a literal }".into()),
+                        _ => result.push(c)
+                    }
+                }
+
+                if placeholders != params.len() - 1 {
+                    return Err(format!("format string has {} {{}} placeholders but {} values were given", placeholders, params.len() - 1).into());
+                }
+
+                Ok(Str(result))
+            }),
+        );
+
+        self.add_builtin(
+            "conjugate",
+            BuiltinFunction::new(1, |params, _| {
+                let num =
+                    params[0].expect_complex("expected a complex number to find conjugate of")?;
+                Ok(Number(Complex64::new(num.re, -num.im)))
+            }),
+        );
+
+        self.add_builtin(
+            "Re",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0]
+                    .expect_complex("expected a complex number to find real part of")?;
+
+                Ok(Value::real(num.re))
+            }),
+        );
+
+        self.add_builtin(
+            "Im",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0]
+                    .expect_complex("expected a complex number to find imaginary part of")?;
+
+                Ok(Value::real(num.im))
+            }),
+        );
+
+        self.add_builtin(
+            "arg",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a complex number to find argument of")?;
+                Ok(Value::real(num.arg()))
+            }),
+        );
+
+        self.add_builtin(
+            "phase",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a complex number to find phase of")?;
+                Ok(Value::real(num.arg()))
+            }),
+        );
+
+        self.add_builtin(
+            "modulus",
+            BuiltinFunction::new(1, |params, _| {
+                let num = params[0].expect_complex("expected a complex number to find modulus of")?;
+                Ok(Value::real(num.norm()))
+            }),
+        );
+
+        self.add_builtin(
+            "complex",
+            BuiltinFunction::new(2, |params, _| {
+                let re = params[0].expect_real("expected a real number as the real part in complex(re, im)")?;
+                let im = params[1].expect_real("expected a real number as the imaginary part in complex(re, im)")?;
+                Ok(Number(Complex64::new(re, im)))
+            }),
+        );
+
+        self.add_builtin(
+            "polar",
+            BuiltinFunction::new(2, |params, _| {
+                let r = params[0].expect_real("expected a real modulus in polar(r, theta)")?;
+                let theta = params[1].expect_real("expected a real angle in polar(r, theta)")?;
+                Ok(Number(Complex64::from_polar(r, theta)))
+            }),
+        );
+
+        self.add_builtin(
+            "to_polar",
+            BuiltinFunction::new(1, |params, _| {
+                let (r, theta) = params[0]
+                    .expect_complex("expected a complex number to convert to polar form")?
+                    .to_polar();
+
+                Ok(Value::array(vec![Value::real(r), Value::real(theta)]))
+            }),
+        );
+
+        // arguments are evaluated before a builtin is called,
+        // so unlike in most languages, and() and or() do not short-circuit
+        self.add_builtin(
+            "and",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_truthy("expected a boolean or a real number in and(a, b)")?;
+                let b = params[1].expect_truthy("expected a boolean or a real number in and(a, b)")?;
+                Ok(Boolean(a && b))
+            }),
+        );
+
+        self.add_builtin(
+            "or",
+            BuiltinFunction::new(2, |params, _| {
+                let a = params[0].expect_truthy("expected a boolean or a real number in or(a, b)")?;
+                let b = params[1].expect_truthy("expected a boolean or a real number in or(a, b)")?;
+                Ok(Boolean(a || b))
+            }),
+        );
+
+        self.add_builtin(
+            "not",
+            BuiltinFunction::new(1, |params, _| {
+                let a = params[0].expect_truthy("expected a boolean or a real number in not(a)")?;
+                Ok(Boolean(!a))
+            }),
+        );
+
+        self.add_builtin(
+            "piecewise",
+            BuiltinFunction::variadic(|params, _| {
+                if params.len() % 2 == 0 {
+                    return Err("expected condition and value pairs followed by a default value in piecewise(condition, value, ..., default)".into());
+                }
+
+                for pair in params.chunks(2) {
+                    if let [condition, value] = pair {
+                        if condition.expect_truthy("expected a boolean or a real number as a condition in piecewise(condition, value, ..., default)")? {
+                            return Ok(value.clone());
+                        }
+                    }
+                }
+
+                Ok(params.last().unwrap().clone())
+            }),
+        );
+
+        self.add_builtin(
+            "len",
+            BuiltinFunction::new(1, |params, _| {
+                match &params[0] {
+                    Array(array) => Ok(Value::real(array.len() as f64)),
+                    Str(string) => Ok(Value::real(string.chars().count() as f64)),
+                    _ => Err("expected an array or string to find length of".into())
+                }
+            }),
+        );
+
+        self.add_builtin(
+            "chars",
+            BuiltinFunction::new(1, |params, state| {
+                let string = params[0].expect_string("expected a string to split into characters")?;
+                state.check_elements(string.chars().count() as f64)?;
+                Ok(Value::array(string.chars().map(|c| Str(c.to_string())).collect()))
+            }),
+        );
+
+        // append is another name for push
+        let push = BuiltinFunction::new(2, |params, state| {
+            let mut array = params[0].expect_array("expected an array to add a value to the end of")?.clone();
+            state.check_elements(array.len() as f64 + 1.0)?;
+            array.push(params[1].clone());
+            Ok(Value::array(array))
+        });
+
+        self.add_builtin("push", push.clone());
+        self.add_builtin("append", push);
+
+        self.add_builtin(
+            "rm",
+            BuiltinFunction::new(2, |params, _| {
+                let mut array = params[0].expect_array("expected an array to remove value from")?.clone();
+                let index = params[1].expect_real("expected a real number to index array with in rm(x, y)")?;
+
+                match resolve_index(index, array.len()) {
+                    Some(index) => {
+                        array.remove(index);
+                        Ok(Value::array(array))
+                    }
+                    None => Err(format!("cannot index array in rm(x, y) where y is {}", index).into())
+                }
+            })
+        );
+
+        self.add_builtin(
+            "ins",
+            BuiltinFunction::new(3, |params, state| {
+                let mut array = params[0].expect_array("expected an array to remove value from")?.clone();
+                let index = params[1].expect_real("expected a real number to index array with in ins(x, y, z)")?;
+                state.check_elements(array.len() as f64 + 1.0)?;
+                let value = params[2].clone();
+
+                match resolve_index(index, array.len()) {
+                    Some(index) => {
+                        array.insert(index, value);
+                        Ok(Value::array(array))
+                    }
+                    None => Err(format!("cannot index array in ins(x, y, z) where y is {}", index).into())
+                }
+            })
+        );
+
+        self.add_builtin(
+            "min",
+            BuiltinFunction::variadic(|params, _| {
+                // either a single array, or the values themselves
+                let values = match params {
+                    [Array(arr)] => arr.as_slice(),
+                    _ => params,
+                };
+
+                if values.is_empty() {
+                    return Err("cannot find minimum of an empty array".into());
+                }
+
+                let reals = Value::ordered_reals(values, "expected real numbers to find minimum of")?;
+
+                Ok(Value::real(reals.into_iter().fold(f64::INFINITY, f64::min)))
+            })
+        );
+
+        self.add_builtin(
+            "max",
+            BuiltinFunction::variadic(|params, _| {
+                // either a single array, or the values themselves
+                let values = match params {
+                    [Array(arr)] => arr.as_slice(),
+                    _ => params,
+                };
+
+                if values.is_empty() {
+                    return Err("cannot find maximum of an empty array".into());
+                }
+
+                let reals = Value::ordered_reals(values, "expected real numbers to find maximum of")?;
+
+                Ok(Value::real(reals.into_iter().fold(f64::NEG_INFINITY, f64::max)))
+            })
+        );
+
+        self.add_builtin(
+            "sum",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to find sum of")?;
+
+                Value::sum(array)
+            })
+        );
+
+        self.add_builtin(
+            "cumsum",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to find cumulative sums of")?;
+                let mut sums: Vec<Value> = Vec::with_capacity(array.len());
+
+                for value in array {
+                    let sum = match sums.last() {
+                        Some(sum) => (sum.clone() + value.clone())?,
+                        None => value.clone()
+                    };
+
+                    sums.push(sum);
+                }
+
+                Ok(Value::array(sums))
+            })
+        );
+
+        self.add_builtin(
+            "product",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to find product of")?;
+
+                match array.split_first() {
+                    Some((first, rest)) => rest.iter().try_fold(first.clone(), |product, value| product * value.clone()),
+                    None => Ok(Value::real(1.0))
+                }
+            })
+        );
+
+        self.add_builtin(
+            "mean",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to find mean of")?;
+
+                if array.is_empty() {
+                    return Err("cannot find mean of an empty array".into());
+                }
+
+                Value::sum(array)? / Value::real(array.len() as f64)
+            })
+        );
+
+        self.add_builtin(
+            "median",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to find median of")?;
+
+                if array.is_empty() {
+                    return Err("cannot find median of an empty array".into());
+                }
+
+                let mut reals = Value::ordered_reals(array, "expected an array of real numbers to find median of")?;
+                reals.sort_by(f64::total_cmp);
+
+                let middle = reals.len() / 2;
+
+                if reals.len() % 2 == 0 {
+                    Ok(Value::real((reals[middle - 1] + reals[middle]) / 2.0))
+                } else {
+                    Ok(Value::real(reals[middle]))
+                }
+            })
+        );
+
+        self.add_builtin(
+            "variance",
+            BuiltinFunction::new(1, |params, _| {
+                let reals = statistics_reals(&params[0], "variance")?;
+                Ok(Value::real(variance(&reals)))
+            })
+        );
+
+        self.add_builtin(
+            "stddev",
+            BuiltinFunction::new(1, |params, _| {
+                let reals = statistics_reals(&params[0], "standard deviation")?;
+                Ok(Value::real(variance(&reals).sqrt()))
+            })
+        );
+
+        self.add_builtin(
+            "clamp",
+            BuiltinFunction::new(3, |params, _| {
+                let x = params[0].expect_real("expected a real number to clamp in clamp(x, lo, hi)")?;
+                let lo = params[1].expect_real("expected a real lower bound in clamp(x, lo, hi)")?;
+                let hi = params[2].expect_real("expected a real upper bound in clamp(x, lo, hi)")?;
+
+                if lo > hi {
+                    Err(format!("cannot clamp between {} and {}, the lower bound is greater than the upper bound", lo, hi).into())
+                } else if x < lo {
+                    Ok(Value::real(lo))
+                } else if x > hi {
+                    Ok(Value::real(hi))
+                } else {
+                    Ok(Value::real(x))
+                }
+            })
+        );
+
+        self.add_builtin(
+            "lerp",
+            BuiltinFunction::new(3, |params, _| {
+                let t = params[2].expect_real("expected a real number to interpolate by in lerp(a, b, t)")?;
+                let a = params[0].clone();
+                let b = params[1].clone();
+
+                // t is not clamped, so values outside of [0, 1] extrapolate past a and b
+                a.clone() + ((b - a)? * Value::real(t))?
+            })
+        );
+
+        self.add_builtin(
+            "sort",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to sort")?;
+                let mut reals = Value::ordered_reals(array, "expected an array of real numbers to sort")?;
+
+                reals.sort_by(f64::total_cmp);
+                Ok(Value::array(reals.into_iter().map(Value::real).collect()))
+            })
+        );
+
+        self.add_builtin(
+            "sortdesc",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to sort")?;
+                let mut reals = Value::ordered_reals(array, "expected an array of real numbers to sort")?;
+
+                reals.sort_by(|a, b| b.total_cmp(a));
+                Ok(Value::array(reals.into_iter().map(Value::real).collect()))
+            })
+        );
+
+        self.add_builtin(
+            "reverse",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to reverse")?;
+                Ok(Value::array(array.iter().rev().cloned().collect()))
+            })
+        );
+
+        self.add_builtin(
+            "head",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to find the head of")?;
+
+                match array.first() {
+                    Some(value) => Ok(value.clone()),
+                    None => Err("cannot find the head of an empty array".into())
+                }
+            })
+        );
+
+        self.add_builtin(
+            "tail",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to find the tail of")?;
+                Ok(Value::array(array.iter().skip(1).cloned().collect()))
+            })
+        );
+
+        self.add_builtin(
+            "take",
+            BuiltinFunction::new(2, |params, _| {
+                let array = params[0].expect_array("expected an array to take elements from in take(x, n)")?;
+                let n = params[1].expect_count("expected a non-negative integer count in take(x, n)")?;
+                Ok(Value::array(array.iter().take(n).cloned().collect()))
+            })
+        );
+
+        self.add_builtin(
+            "drop",
+            BuiltinFunction::new(2, |params, _| {
+                let array = params[0].expect_array("expected an array to drop elements from in drop(x, n)")?;
+                let n = params[1].expect_count("expected a non-negative integer count in drop(x, n)")?;
+                Ok(Value::array(array.iter().skip(n).cloned().collect()))
+            })
+        );
+
+        self.add_builtin(
+            "concat",
+            BuiltinFunction::new(2, |params, state| {
+                let first = params[0].expect_array("expected an array as the first argument of concat(x, y)")?;
+                let second = params[1].expect_array("expected an array as the second argument of concat(x, y)")?;
+                state.check_elements(first.len() as f64 + second.len() as f64)?;
+                Ok(Value::array(first.iter().chain(second.iter()).cloned().collect()))
+            })
+        );
+
+        self.add_builtin(
+            "unique",
+            BuiltinFunction::new(1, |params, _| {
+                let array = params[0].expect_array("expected an array to remove duplicates from")?;
+                let mut unique = Vec::new();
+
+                // keeps the first of each duplicate, so the order elements were first seen in is preserved
+                for value in array {
+                    if !unique.contains(value) {
+                        unique.push(value.clone());
+                    }
+                }
+
+                Ok(Value::array(unique))
+            })
+        );
+
+        self.add_builtin(
+            "flatten",
+            BuiltinFunction::new(1, |params, state| {
+                let array = params[0].expect_array("expected an array to flatten")?;
+                let mut flattened = Vec::new();
+
+                flatten(array, &mut flattened, state)?;
+                Ok(Value::array(flattened))
+            })
+        );
+
+        self.add_builtin(
+            "contains",
+            BuiltinFunction::new(2, |params, _| {
+                let array = params[0].expect_array("expected an array to search in contains(x, value)")?;
+                Ok(Boolean(array.contains(&params[1])))
+            })
         );
 
         self.add_builtin(
-            "logn",
+            "indexof",
             BuiltinFunction::new(2, |params, _| {
-                let n = params[0].expect_real("expected real base to logarithm")?;
-                let x = params[1].expect_complex("expected number to find logarithm of")?;
-                Ok(Number(x.log(n)))
-            }),
+                let array = params[0].expect_array("expected an array to search in indexof(x, value)")?;
+
+                match array.iter().position(|element| element == &params[1]) {
+                    Some(index) => Ok(Value::real(index as f64)),
+                    None => Ok(Value::real(-1.0))
+                }
+            })
         );
 
         self.add_builtin(
-            "ln",
-            BuiltinFunction::new(1, |params, _| {
-                let num =
-                    params[0].expect_complex("expected number to find natural logarithm of")?;
-                Ok(Number(num.ln()))
-            }),
+            "zip",
+            BuiltinFunction::new(2, |params, _| {
+                let first = params[0].expect_array("expected an array as the first argument of zip(x, y)")?;
+                let second = params[1].expect_array("expected an array as the second argument of zip(x, y)")?;
+
+                Ok(Value::array(first
+                    .iter()
+                    .zip(second.iter())
+                    .map(|(a, b)| Value::array(vec![a.clone(), b.clone()]))
+                    .collect()))
+            })
         );
 
         self.add_builtin(
-            "print",
-            BuiltinFunction::new(1, |params, _| {
-                println!("{}", params[0]);
-                Ok(params[0].clone())
-            }),
+            "fill",
+            BuiltinFunction::new(2, |params, state| {
+                let count = params[1].expect_count("expected a non-negative integer count in fill(value, count)")?;
+                state.check_elements(count as f64)?;
+                Ok(Value::array(vec![params[0].clone(); count]))
+            })
         );
 
         self.add_builtin(
-            "conjugate",
-            BuiltinFunction::new(1, |params, _| {
-                let num =
-                    params[0].expect_complex("expected a complex number to find conjugate of")?;
-                Ok(Number(Complex64::new(num.re, -num.im)))
-            }),
+            "slice",
+            BuiltinFunction::new(3, |params, _| {
+                let array = params[0].expect_array("expected an array to slice")?;
+                let start = params[1].expect_real("expected a real number as the start of slice(x, start, end)")?;
+                let end = params[2].expect_real("expected a real number as the end of slice(x, start, end)")?;
+
+                if start.fract() != 0.0 || start < 0.0 {
+                    Err(format!("cannot slice array in slice(x, start, end) where start is {}", start).into())
+                } else if end.fract() != 0.0 || end < 0.0 || end > array.len() as f64 {
+                    Err(format!("cannot slice array of length {} in slice(x, start, end) where end is {}", array.len(), end).into())
+                } else if start > end {
+                    Err(format!("cannot slice array in slice(x, start, end) where start ({}) is greater than end ({})", start, end).into())
+                } else {
+                    Ok(Value::array(array[start as usize..end as usize].to_vec()))
+                }
+            })
         );
 
         self.add_builtin(
-            "Re",
-            BuiltinFunction::new(1, |params, _| {
-                let num = params[0]
-                    .expect_complex("expected a complex number to find real part of")?;
-
-                Ok(Value::real(num.re))
-            }),
+            "range",
+            BuiltinFunction::new(3, |params, state| {
+                let first = params[0].expect_real("the first bound must be a real number")?;
+                let second = params[1].expect_real("the second bound must be a real number")?;
+                let step = params[2].expect_real("the step must be a number")?;
+
+                check_range(first, second, step)?;
+                state.check_elements(((second - first) / step).abs().ceil() + 1.0)?;
+                Ok(Value::array(range_values(first, second, step).map(Value::real).collect()))
+            })
         );
 
         self.add_builtin(
-            "Im",
-            BuiltinFunction::new(1, |params, _| {
-                let num = params[0]
-                    .expect_complex("expected a complex number to find imaginary part of")?;
+            "map",
+            BuiltinFunction::new(2, |params, state| {
+                let array = params[0].expect_array("expected an array to map over in map(x, f)")?;
+                let function = params[1].expect_function("expected a function to map with in map(x, f)")?;
 
-                Ok(Value::real(num.im))
-            }),
+                Ok(Value::array(array
+                    .iter()
+                    .map(|value| state.call_function(function, vec![value.clone()]))
+                    .collect::<Result<Vec<Value>, RuntimeError>>()?))
+            })
         );
 
         self.add_builtin(
-            "len",
-            BuiltinFunction::new(1, |params, _| {
-                let array =
-                    params[0].expect_array("expected an array to find length of")?;
-                Ok(Value::real(array.len() as f64))
-            }),
+            "zipwith",
+            BuiltinFunction::new(3, |params, state| {
+                let first = params[0].expect_array("expected an array as the first argument of zipwith(x, y, f)")?;
+                let second = params[1].expect_array("expected an array as the second argument of zipwith(x, y, f)")?;
+                let function = params[2].expect_function("expected a function to combine with in zipwith(x, y, f)")?;
+
+                if first.len() != second.len() {
+                    return Err(format!("cannot zipwith arrays of different lengths {} and {}", first.len(), second.len()).into());
+                }
+
+                Ok(Value::array(first
+                    .iter()
+                    .zip(second.iter())
+                    .map(|(a, b)| state.call_function(function, vec![a.clone(), b.clone()]))
+                    .collect::<Result<Vec<Value>, RuntimeError>>()?))
+            })
         );
 
         self.add_builtin(
-            "rm",
-            BuiltinFunction::new(2, |params, _| {
-                let mut array = params[0].expect_array("expected an array to remove value from")?.clone();
-                let index = params[1].expect_real("expected a real number to index array with in rm(x, y)")?;
-
-                if index.fract() != 0.0 || index < 0.0 || index >= array.len() as f64 {
-                    Err(format!("cannot index array in rm(x, y) where y is {}", index))
-                } else {
-                    array.remove(index as usize);
-                    Ok(Value::Array(array))
+            "filter",
+            BuiltinFunction::new(2, |params, state| {
+                let array = params[0].expect_array("expected an array to filter in filter(x, f)")?;
+                let predicate = params[1].expect_function("expected a function to filter with in filter(x, f)")?;
+                let mut filtered = Vec::new();
+
+                for value in array {
+                    let keep = state
+                        .call_function(predicate, vec![value.clone()])?
+                        .expect_truthy("the function in filter(x, f) must return a boolean or a real number")?;
+
+                    if keep {
+                        filtered.push(value.clone());
+                    }
                 }
+
+                Ok(Value::array(filtered))
             })
         );
 
         self.add_builtin(
-            "ins",
-            BuiltinFunction::new(3, |params, _| {
-                let mut array = params[0].expect_array("expected an array to remove value from")?.clone();
-                let index = params[1].expect_real("expected a real number to index array with in ins(x, y, z)")?;
-                let value = params[2].clone();
+            "reduce",
+            BuiltinFunction::new(3, |params, state| {
+                let array = params[0].expect_array("expected an array to reduce in reduce(x, f, initial)")?;
+                let function = params[1].expect_function("expected a function to reduce with in reduce(x, f, initial)")?;
 
-                if index.fract() != 0.0 || index < 0.0 || index >= array.len() as f64 {
-                    Err(format!("cannot index array in ins(x, y, z) where y is {}", index))
-                } else {
-                    array.insert(index as usize, value);
-                    Ok(Value::Array(array))
+                if let Some(parameter_count) = state.parameter_count(function) {
+                    if parameter_count != 2 {
+                        return Err(format!(
+                            "the function in reduce(x, f, initial) must take 2 parameters, but {} takes {}",
+                            function, parameter_count
+                        ).into());
+                    }
                 }
+
+                array.iter().try_fold(params[2].clone(), |accumulator, value| {
+                    state.call_function(function, vec![accumulator, value.clone()])
+                })
+            })
+        );
+
+        self.add_builtin(
+            "typeof",
+            BuiltinFunction::new(1, |params, _| {
+                Ok(Str(params[0].kind().to_string()))
             })
         );
 
@@ -667,6 +2828,10 @@ impl<'a> RuntimeState<'a> {
         self.locals.remove(name);
     }
 
+    fn remove_global(&mut self, name: &'a str) {
+        self.globals.remove(name);
+    }
+
     fn has_local(&self, name: &'a str) -> bool {
         self.locals.contains_key(name)
     }
@@ -679,6 +2844,28 @@ impl<'a> RuntimeState<'a> {
         self.functions.insert(name, body);
     }
 
+    fn remove_function(&mut self, name: &'a str) {
+        self.functions.remove(name);
+    }
+
+    // a value as print and format show it, with the precision set by set_precision
+    fn display(&self, value: &Value) -> String {
+        match self.precision {
+            Some(digits) => format!("{:.*}", digits, value),
+            None => value.to_string()
+        }
+    }
+
+    fn print_line(&mut self, line: &str) {
+        match &mut self.captured_output {
+            Some(output) => {
+                output.push_str(line);
+                output.push('\n');
+            }
+            None => println!("{}", line)
+        }
+    }
+
     fn add_builtin(&mut self, name: &'a str, function: BuiltinFunction) {
         self.builtin_functions.insert(name, function);
     }
@@ -687,7 +2874,174 @@ impl<'a> RuntimeState<'a> {
         self.functions.contains_key(name) || self.builtin_functions.contains_key(name)
     }
 
-    fn evaluate(&mut self, node: &'a ParserNode<'a>) -> Result<Value, String> {
+    // the number of parameters a function takes, or None if it takes any number of them
+    fn parameter_count(&self, name: &str) -> Option<usize> {
+        match self.builtin_functions.get(name) {
+            Some(builtin) => builtin.parameter_count,
+            None => match self.functions.get(name) {
+                Some(ParserNode::FunctionDeclaration(_, parameters, _, _))
+                | Some(ParserNode::Lambda(_, parameters, _)) => Some(parameters.len()),
+                _ => None
+            }
+        }
+    }
+
+    // checked before a builtin makes an array or matrix, since running out of memory would abort the host as well.
+    // the count is a float so sizes that multiply to more than fits in a usize are still caught
+    fn check_elements(&self, count: f64) -> Result<(), RuntimeError> {
+        match self.limits.elements {
+            Some(limit) if count > limit as f64 => Err(ElementLimitExceeded(limit).into()),
+            _ => Ok(())
+        }
+    }
+
+    // only checked on function calls and loop iterations, since reading the clock on every node would be slow
+    fn check_timeout(&self) -> Result<(), RuntimeError> {
+        match self.limits.timeout {
+            Some(timeout) if self.start_instant.elapsed() > timeout => Err(Timeout(timeout).into()),
+            _ => Ok(())
+        }
+    }
+
+    // evaluates the body of a loop once for each value, with the parameter bound to it, and combines the results
+    fn run_loop(&mut self, parameter: &'a str, mode: &LoopMode, values: impl Iterator<Item = Value>, body: &'a ParserNode<'a>) -> ValueOutput {
+        let shadowed_local = self.locals.remove(parameter);
+        let result = self.loop_bodies(parameter, mode, values, body);
+
+        // the parameter only exists inside the loop, so whatever it shadowed comes back even if the body errored
+        match shadowed_local {
+            Some(value) => self.add_local(parameter, value),
+            None => self.remove_local(parameter),
+        }
+
+        result
+    }
+
+    // sums and products start from the first result rather than 0 or 1, so loops can combine vectors and arrays too
+    fn loop_bodies(&mut self, parameter: &'a str, mode: &LoopMode, values: impl Iterator<Item = Value>, body: &'a ParserNode<'a>) -> ValueOutput {
+        let mut combined = None;
+        let mut collected = Vec::new();
+
+        for value in values {
+            self.check_timeout()?;
+            self.add_local(parameter, value);
+
+            let result = self.evaluate(body)?;
+
+            if let LoopMode::Collect = mode {
+                self.check_elements(collected.len() as f64 + 1.0)?;
+                collected.push(result);
+                continue;
+            }
+
+            combined = Some(match (combined, mode) {
+                (Some(sum), LoopMode::Sum) => (sum + result)?,
+                (Some(product), LoopMode::Product) => (product * result)?,
+                _ => result,
+            });
+        }
+
+        Ok(match mode {
+            LoopMode::Collect => Value::array(collected),
+            LoopMode::Product => combined.unwrap_or_else(|| Value::real(1.0)),
+            _ => combined.unwrap_or_else(|| Value::real(0.0)),
+        })
+    }
+
+    // assigning updates the variable where it was declared, unlike let which always makes a new one
+    fn assign(&mut self, identifier: &'a str, value: Value) -> Result<(), RuntimeError> {
+        if self.has_local(identifier) {
+            self.add_local(identifier, value);
+        } else if !self.has_global(identifier) {
+            return Err(UndefinedVariable(identifier.to_string()).into());
+        } else if self.in_function {
+            return Err(format!(
+                "attempted to affect external variable {} from within a function, use `global {} = ...` if that is intended",
+                identifier, identifier
+            ).into());
+        } else {
+            self.add_global(identifier, value);
+        }
+
+        Ok(())
+    }
+
+    // calls a builtin or declared function with arguments that are already evaluated
+    fn call_function(&mut self, name: &str, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+        if let Some(builtin) = self.builtin_functions.get(name) {
+            if let Some(parameter_count) = builtin.parameter_count {
+                if arguments.len() != parameter_count {
+                    return Err(ArityMismatch(name.to_string(), parameter_count, arguments.len()).into());
+                }
+            }
+
+            let body = builtin.body.clone();
+            return body(&arguments, self);
+        }
+
+        if let Some(ParserNode::FunctionDeclaration(_, parameters, body, _))
+        | Some(ParserNode::Lambda(_, parameters, body)) = self.functions.get(name).copied() {
+            if arguments.len() != parameters.len() {
+                return Err(ArityMismatch(name.to_string(), parameters.len(), arguments.len()).into());
+            }
+
+            if self.depth >= self.limits.recursion {
+                return Err(RecursionLimitExceeded(self.limits.recursion).into());
+            }
+
+            self.check_timeout()?;
+
+            // binding an argument hands back the local it shadows, which is moved back in afterwards rather than copied
+            let shadowed_locals = parameters
+                .iter()
+                .zip(arguments)
+                .map(|(parameter, argument)| (*parameter, self.locals.insert(parameter, argument)))
+                .collect::<Vec<(&str, Option<Value>)>>();
+
+            let in_function = self.in_function;
+            self.in_function = true;
+
+            // the state is restored even when the body errors, so the depth never drifts
+            self.depth += 1;
+            let result = self.evaluate(body);
+            self.depth -= 1;
+
+            // restored in reverse, so a parameter name used twice ends up with the value it had before the call
+            for (parameter, shadowed) in shadowed_locals.into_iter().rev() {
+                match shadowed {
+                    Some(value) => self.add_local(parameter, value),
+                    None => self.remove_local(parameter),
+                }
+            }
+
+            self.in_function = in_function;
+
+            result
+        } else {
+            Err(UndefinedFunction(name.to_string()).into())
+        }
+    }
+
+    // evaluates a node, pointing any error at the innermost node that knows where it is in the source
+    fn evaluate(&mut self, node: &'a ParserNode<'a>) -> Result<Value, RuntimeError> {
+        match (self.evaluate_node(node), node.span()) {
+            (Err(mut error), Some(span)) if error.location.is_none() => {
+                error.location = Some(span);
+                Err(error)
+            }
+            (result, _) => result,
+        }
+    }
+
+    fn evaluate_node(&mut self, node: &'a ParserNode<'a>) -> Result<Value, RuntimeError> {
+        self.steps += 1;
+
+        if let Some(limit) = self.limits.steps {
+            if self.steps > limit {
+                return Err(BudgetExceeded(limit).into());
+            }
+        }
+
         match node {
             ParserNode::Number(num, imaginary) => {
                 if *imaginary {
@@ -696,18 +3050,29 @@ impl<'a> RuntimeState<'a> {
                     Ok(Value::real(*num))
                 }
             }
-            ParserNode::Identifier(identifier) => {
+            ParserNode::Str(string) => Ok(Str(string.to_string())),
+            ParserNode::Identifier(identifier, _) => {
                 if self.has_local(identifier) {
                     Ok(self.locals[identifier].clone())
                 } else if self.has_global(identifier) {
                     Ok(self.globals[identifier].clone())
+                } else if self.has_function(identifier) {
+                    Ok(Function(identifier.to_string()))
                 } else {
-                    return Err(format!("unknown variable: {}", identifier));
+                    Err(UndefinedVariable(identifier.to_string()).into())
                 }
             }
-            ParserNode::Operation(left, operator, right) => {
-                let left = self.evaluate(&*left)?;
-                let right = self.evaluate(&*right)?;
+            ParserNode::Operation(left, Operator::And, right, _) => {
+                let message = "expected a boolean or a real number on either side of &&";
+                Ok(Boolean(self.evaluate(left)?.expect_truthy(message)? && self.evaluate(right)?.expect_truthy(message)?))
+            }
+            ParserNode::Operation(left, Operator::Or, right, _) => {
+                let message = "expected a boolean or a real number on either side of ||";
+                Ok(Boolean(self.evaluate(left)?.expect_truthy(message)? || self.evaluate(right)?.expect_truthy(message)?))
+            }
+            ParserNode::Operation(left, operator, right, _) => {
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
 
                 Ok(match operator {
                     Operator::Add => (left + right)?,
@@ -717,128 +3082,67 @@ impl<'a> RuntimeState<'a> {
                     Operator::Power => left.pow(right)?,
                     Operator::Modulo => (left % right)?,
                     Operator::Equals => left.equals(right),
+                    Operator::NotEquals => left.not_equals(right),
                     Operator::GreaterThan => left.greater_than(right)?,
                     Operator::LessThan => left.less_than(right)?,
                     Operator::GreaterThanOrEquals => left.greater_than_or_equals(right)?,
                     Operator::LessThanOrEquals => left.less_than_or_equals(right)?,
+                    Operator::And | Operator::Or => unreachable!(),
                 })
             }
-            ParserNode::FunctionCall(name, arguments) => {
-                if !self.has_function(name) {
-                    return Err(format!("unknown function: {}", name));
-                }
-
-                self.in_function = true;
-
-                if self.builtin_functions.contains_key(name) {
-                    if arguments.len() != self.builtin_functions[name].parameter_count {
-                        return Err(format!(
-                            "{} expects {} parameters, but only {} were supplied",
-                            name,
-                            self.builtin_functions[name].parameter_count,
-                            arguments.len()
-                        ));
-                    }
-
-                    let evaluated_arguments: Vec<Result<Value, String>> = arguments
-                        .iter()
-                        .map(|argument| self.evaluate(argument))
-                        .collect();
-
-                    for argument in evaluated_arguments.iter() {
-                        if let Err(_) = argument {
-                            return argument.clone();
-                        }
-                    }
-
-                    let mut evaluated_arguments: Vec<Value> = evaluated_arguments
-                        .into_iter()
-                        .map(|argument| argument.unwrap())
-                        .collect();
-
-                    return (self.builtin_functions[name].body)(&mut evaluated_arguments, &self);
-                }
-
-                let mut functions = self
-                    .functions
+            ParserNode::FunctionCall(name, arguments, _) => {
+                let arguments = arguments
                     .iter()
-                    .filter(|function| *function.0 == *name)
-                    .map(|function| *function.1)
-                    .collect::<Vec<&ParserNode>>();
-
-                if let ParserNode::FunctionDeclaration(_, parameters, body) =
-                functions.pop().unwrap()
-                {
-                    if arguments.len() != parameters.len() {
-                        return Err(format!(
-                            "{} expects {} parameters, but only {} were supplied",
-                            name,
-                            parameters.len(),
-                            arguments.len()
-                        ));
-                    }
-
-                    let mut preserved_locals = HashMap::new();
-
-                    for parameter in parameters.iter() {
-                        if self.has_local(parameter) {
-                            preserved_locals.insert(*parameter, self.locals[parameter].clone());
-                        }
-                    }
+                    .map(|argument| self.evaluate(argument))
+                    .collect::<Result<Vec<Value>, RuntimeError>>()?;
 
-                    for i in 0..arguments.len() {
-                        let arg_value = self.evaluate(&arguments[i])?;
-                        self.add_local(parameters[i], arg_value);
-                    }
-
-                    let result = self.evaluate(&*body)?;
-
-                    for i in 0..parameters.len() {
-                        let parameter = parameters[i];
-
-                        if !preserved_locals.contains_key(parameter) {
-                            self.remove_local(parameter);
-                        } else {
-                            self.add_local(parameter, preserved_locals[parameter].clone());
-                        }
-                    }
+                if self.has_function(name) {
+                    return self.call_function(name, arguments);
+                }
 
-                    self.in_function = false;
+                // the name may also be a variable holding a function, such as a parameter
+                let function = match self.locals.get(name).or_else(|| self.globals.get(name)) {
+                    Some(Function(function)) => function.clone(),
+                    _ => return Err(UndefinedFunction(name.to_string()).into())
+                };
 
-                    return Ok(result);
-                } else {
-                    unreachable!()
-                }
+                self.call_function(&function, arguments)
             }
             ParserNode::Conditional(predicate, true_expr, false_expr) => {
                 let predicate = self
-                    .evaluate(&*predicate)?
-                    .expect_real("a predicate to a conditional expression must be a number")?;
+                    .evaluate(predicate)?
+                    .expect_predicate("a conditional expression")?;
 
-                if predicate != 0.0 {
-                    return Ok(self.evaluate(&*true_expr)?);
+                if predicate {
+                    self.evaluate(true_expr)
                 } else {
-                    return Ok(self.evaluate(&*false_expr)?);
+                    self.evaluate(false_expr)
                 }
             }
-            ParserNode::FunctionDeclaration(name, _, _) => {
+            ParserNode::FunctionDeclaration(name, _, _, _) => {
                 if self.has_function(name) {
                     return Err(format!(
                         "redeclared a function that already is defined: {}",
                         name
-                    ));
+                    ).into());
                 }
 
                 self.add_function(name, node);
 
                 Ok(Value::real(0.0))
             }
-            ParserNode::VariableDeclaration(name, expression) => {
+            ParserNode::Lambda(source, _, _) => {
+                // the same source always makes the same function, since lambdas don't capture anything
+                self.add_function(source, node);
+
+                Ok(Function(source.to_string()))
+            }
+            ParserNode::VariableDeclaration(name, expression, _) => {
                 if self.has_global(name) || self.has_local(name) {
-                    return Err(format!("you cannot redeclare a variable: {}", name));
+                    return Err(format!("you cannot redeclare a variable: {}", name).into());
                 }
 
-                let value = self.evaluate(&*expression)?;
+                let value = self.evaluate(expression)?;
 
                 if self.in_function {
                     self.add_local(name, value);
@@ -848,133 +3152,132 @@ impl<'a> RuntimeState<'a> {
 
                 Ok(Value::real(0.0))
             }
-            ParserNode::Loop(parameter, range, body) => {
-                if let ParserNode::Range(first, second, step) = &**range
-                /* :S */
-                {
+            ParserNode::Loop(parameter, mode, iterable, body, _) => {
+                if let ParserNode::Range(first, second, step) = &**iterable {
                     let first_bound = self
-                        .evaluate(&*first)?
+                        .evaluate(first)?
                         .expect_real("the first bound must be a real number")?;
                     let second_bound = self
-                        .evaluate(&*second)?
+                        .evaluate(second)?
                         .expect_real("the second bound must be a real number")?;
                     let step = self
-                        .evaluate(&*step)?
+                        .evaluate(step)?
                         .expect_real("the step must be a number")?;
 
-                    if step == 0.0 {
-                        return Err("a step cannot be 0".to_string());
-                    }
-
-                    let preserved_local = if self.has_local(parameter) {
-                        Some(self.locals[parameter].clone())
-                    } else {
-                        None
+                    check_range(first_bound, second_bound, step)?;
+                    self.run_loop(parameter, mode, range_values(first_bound, second_bound, step).map(Value::real), body)
+                } else {
+                    let elements = match self.evaluate(iterable)? {
+                        Array(elements) => elements,
+                        value => return Err(TypeMismatch(format!("cannot loop through {}, only ranges and arrays", value.type_name())).into())
                     };
 
-                    let mut x = first_bound;
-                    let mut sum = Value::real(0.0);
+                    self.run_loop(parameter, mode, elements.iter().cloned(), body)
+                }
+            }
+            ParserNode::While(predicate, body, _) => {
+                let mut last_evaluated = Value::real(0.0);
 
-                    if first_bound < second_bound {
-                        while x < second_bound {
-                            self.add_local(parameter, Value::real(x));
-                            sum = (sum + self.evaluate(&*body)?)?;
-                            x = if x + step < second_bound {
-                                x + step
-                            } else {
-                                self.add_local(parameter, Value::real(second_bound));
-                                sum = (sum + self.evaluate(&*body)?)?;
-                                break;
-                            };
-                        }
-                    } else {
-                        while x > second_bound {
-                            self.add_local(parameter, Value::real(x));
-                            sum = (sum + self.evaluate(&*body)?)?;
+                // every pass goes through evaluate, so the step budget and timeout stop a loop that never ends
+                loop {
+                    self.check_timeout()?;
 
-                            x = if x - step > second_bound {
-                                x - step
-                            } else {
-                                self.add_local(parameter, Value::real(second_bound));
-                                sum = (sum + self.evaluate(&*body)?)?;
-                                break;
-                            };
-                        }
-                    }
+                    let predicate = self
+                        .evaluate(predicate)?
+                        .expect_predicate("a while loop")?;
 
-                    if let Some(val) = preserved_local {
-                        self.add_local(parameter, val);
+                    if !predicate {
+                        break;
                     }
 
-                    Ok(sum)
-                } else {
-                    unreachable!()
+                    last_evaluated = self.evaluate(body)?;
                 }
+
+                Ok(last_evaluated)
             }
-            ParserNode::Assignment(identifiers, expression) => {
+            ParserNode::Assignment(identifiers, expression, _) => {
                 let expression = self.evaluate(expression)?;
 
                 for identifier in identifiers.iter() {
-                    if !self.has_local(identifier) && !self.has_global(identifier) {
-                        return Err(format!("use of undefined variable: {}", identifier));
-                    }
-
-                    if self.in_function && self.has_global(identifier) && !self.has_local(identifier ) {
-                        return Err(format!("attempted to affect external variable {} from within a function", identifier));
-                    }
-
-                    self.add_local(identifier, expression.clone());
+                    self.assign(identifier, expression.clone())?;
                 }
 
                 Ok(expression)
             }
-            ParserNode::Factorial(expression) => {
-                let c = self.evaluate(expression)?
-                    .expect_complex("attempted to find factorial of non-number")?;
+            ParserNode::DestructuringAssignment(identifiers, expression, _) => {
+                let expression = self.evaluate(expression)?;
+                let elements = expression.expect_array("only an array can be unpacked into several variables")?;
 
-                if c.im == 0.0 && c.re > 0.0 && c.re.fract() == 0.0 {
-                    let mut factorial = c.re as i64;
+                if elements.len() != identifiers.len() {
+                    return Err(format!(
+                        "cannot unpack an array of {} elements into {} variables",
+                        elements.len(),
+                        identifiers.len()
+                    ).into());
+                }
 
-                    for n in (2..c.re as i64).rev() {
-                        factorial *= n;
-                    }
+                for (identifier, element) in identifiers.iter().zip(elements.iter()) {
+                    self.assign(identifier, element.clone())?;
+                }
 
-                    return Ok(Value::real(factorial as f64));
-                } else if c.re == 0.0 && c.im == 0.0 {
-                    return Ok(Value::real(1.0));
-                } else {
-                    Value::Number(c + 1.0).gamma()
+                Ok(expression)
+            }
+            ParserNode::GlobalAssignment(identifier, expression, _) => {
+                if !self.has_global(identifier) {
+                    return Err(UndefinedVariable(identifier.to_string()).into());
                 }
+
+                let expression = self.evaluate(expression)?;
+                self.add_global(identifier, expression.clone());
+
+                Ok(expression)
             }
+            ParserNode::Negate(expression, _) => {
+                let value = self.evaluate(expression)?;
+                -value
+            }
+            ParserNode::Factorial(expression) => self.evaluate(expression)?.factorial(),
             ParserNode::Tree(nodes) => {
                 if nodes.is_empty() {
                     return Ok(Value::real(0.0));
                 }
 
                 match nodes.last().unwrap() {
-                    ParserNode::VariableDeclaration(_, _)
-                    | ParserNode::FunctionDeclaration(_, _, _) => {
-                        Err("a tree must end with an expression".to_owned())
+                    ParserNode::VariableDeclaration(_, _, _)
+                    | ParserNode::FunctionDeclaration(_, _, _, _) => {
+                        Err("a tree must end with an expression".into())
                     }
                     _ => {
                         let mut last_evaluated = Value::real(0.0);
                         let mut new_locals = Vec::new();
+                        let mut new_functions = Vec::new();
 
-                        for node in nodes.into_iter() {
+                        for node in nodes.iter() {
                             last_evaluated = self.evaluate(node)?;
 
-                            if let ParserNode::VariableDeclaration(name, _) = node {
-                                new_locals.push(name);
-                            } else if let ParserNode::FunctionDeclaration(name, _, _) = node {
+                            if let ParserNode::VariableDeclaration(name, _, _) = node {
                                 new_locals.push(name);
+                            } else if let ParserNode::FunctionDeclaration(name, _, _, _) = node {
+                                new_functions.push(name);
                             }
                         }
 
+                        // outside of functions variable declarations are global, so they're taken back out of the globals.
+                        // along with the functions declared in the tree, this lets a tree that runs more than once,
+                        // such as a loop body, declare them again
                         for local in new_locals {
-                            self.remove_local(local);
+                            if self.in_function {
+                                self.remove_local(local);
+                            } else {
+                                self.remove_global(local);
+                            }
+                        }
+
+                        for function in new_functions {
+                            self.remove_function(function);
                         }
 
-                        return Ok(last_evaluated);
+                        Ok(last_evaluated)
                     }
                 }
             },
@@ -985,32 +3288,157 @@ impl<'a> RuntimeState<'a> {
                     evaluated_expressions.push(self.evaluate(expression)?);
                 }
 
-                Ok(Array(evaluated_expressions))
+                Ok(Value::array(evaluated_expressions))
             },
-            ParserNode::Index(array, index) => {
+            ParserNode::Index(array, index, _) => {
                 let array = self.evaluate(array)?;
                 let array = array.expect_array("cannot index a non-array")?;
 
                 let index = self.evaluate(index)?.expect_real("tried to index using non-number")?;
 
                 if index.fract() != 0.0 {
-                    return Err("cannot index arrays with non-integers".to_owned())
+                    return Err("cannot index arrays with non-integers".into())
                 }
 
-                if index as usize >= array.len() || index < 0.0 {
-                    return Err(format!("attempted to index array of length {} with index {}", array.len(), index))
+                match resolve_index(index, array.len()) {
+                    Some(index) => Ok(array[index].clone()),
+                    None => Err(IndexOutOfBounds(index, array.len()).into())
                 }
-
-                Ok(array[index as usize].clone())
             }
             ParserNode::Range(_, _, _) => unreachable!()
         }
     }
 }
 
-pub fn execute(root: ParserNode) -> Result<Value, String> {
-    let mut runtime = RuntimeState::new();
-    runtime.add_default_globals_and_functions();
-    runtime.start_instant = Instant::now();
-    runtime.evaluate(&root)
+pub fn execute(root: ParserNode) -> Result<Value, RuntimeError> {
+    execute_with_limits(root, Limits::default())
+}
+
+pub fn execute_capturing(root: ParserNode) -> (Result<Value, RuntimeError>, String) {
+    Runtime::new().execute_capturing(root)
+}
+
+pub fn execute_with_timeout(root: ParserNode, timeout: Duration) -> Result<Value, RuntimeError> {
+    execute_with_limits(root, Limits { timeout: Some(timeout), ..Limits::default() })
+}
+
+pub fn execute_with_limits(root: ParserNode, limits: Limits) -> Result<Value, RuntimeError> {
+    Runtime { limits, ..Runtime::new() }.execute(root)
+}
+
+/// A reusable way to run scripts, which host programs can extend with builtin functions of their own.
+///
+/// ```
+/// let runtime = leibniz::Runtime::new();
+/// assert_eq!(runtime.run("2 + 3"), Ok(leibniz::Value::real(5.0)));
+/// ```
+pub struct Runtime {
+    builtins: Vec<(String, BuiltinFunction)>,
+    pub limits: Limits,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Runtime::new()
+    }
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        Runtime {
+            builtins: Vec::new(),
+            limits: Limits::default(),
+        }
+    }
+
+    /// Adds a builtin function that scripts can call. It replaces any builtin of the same name.
+    ///
+    /// ```
+    /// use leibniz::{Runtime, Value};
+    ///
+    /// let mut runtime = Runtime::new();
+    /// runtime.register("double", 1, |params, _| Ok(Value::real(params[0].expect_real("expected a real number")? * 2.0)));
+    /// assert_eq!(runtime.run("double(21)"), Ok(Value::real(42.0)));
+    /// assert!(runtime.run("double(\"a\")").unwrap_err().starts_with("expected a real number"));
+    /// ```
+    pub fn register(
+        &mut self,
+        name: &str,
+        parameter_count: usize,
+        body: impl Fn(&[Value], &mut RuntimeState) -> Result<Value, RuntimeError> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.builtins.push((name.to_string(), BuiltinFunction::new(parameter_count, body)));
+        self
+    }
+
+    /// Parses and runs source code on top of the standard library.
+    /// Parse errors and runtime errors are both returned as the message that would be shown to the user.
+    ///
+    /// ```
+    /// let runtime = leibniz::Runtime::new();
+    /// assert_eq!(runtime.run("sum([1, 2, 3])"), Ok(leibniz::Value::real(6.0)));
+    /// assert!(runtime.run("nothing + 1").unwrap_err().starts_with("unknown variable: nothing"));
+    /// ```
+    pub fn run(&self, source: &str) -> Result<Value, String> {
+        let mut root = crate::parser::parse_leibniz_file(crate::PRELUDE)?;
+        root.append_tree(crate::parser::parse_leibniz_file(source)?);
+        self.execute(root).map_err(|error| error.to_string())
+    }
+
+    /// Runs a parsed script with only the builtin functions, without the standard library.
+    ///
+    /// ```
+    /// use leibniz::{ErrorKind, Runtime};
+    ///
+    /// let root = leibniz::parser::parse_leibniz_file("1 + nothing").unwrap();
+    /// let error = Runtime::new().execute(root).unwrap_err();
+    /// assert_eq!(error.kind, ErrorKind::UndefinedVariable("nothing".to_string()));
+    /// assert_eq!(error.location, Some((1, 5)));
+    /// ```
+    pub fn execute(&self, root: ParserNode) -> Result<Value, RuntimeError> {
+        self.execute_with_output(root, None).0
+    }
+
+    /// Runs the script with everything it prints collected into a string rather than written to stdout.
+    /// The output is given back even when the script fails, with everything printed up until it did.
+    ///
+    /// ```
+    /// let root = leibniz::parser::parse_leibniz_file("print(1)\nprint(\"two\")\n3").unwrap();
+    /// let (result, output) = leibniz::Runtime::new().execute_capturing(root);
+    /// assert_eq!(result, Ok(leibniz::Value::real(3.0)));
+    /// assert_eq!(output, "1\ntwo\n");
+    /// ```
+    pub fn execute_capturing(&self, root: ParserNode) -> (Result<Value, RuntimeError>, String) {
+        let (result, output) = self.execute_with_output(root, Some(String::new()));
+        (result, output.unwrap())
+    }
+
+    fn execute_with_output(&self, root: ParserNode, output: Option<String>) -> (Result<Value, RuntimeError>, Option<String>) {
+        let stack_size = match self.limits.stack_size {
+            Some(stack_size) => stack_size,
+            None => return self.evaluate_root(&root, output),
+        };
+
+        std::thread::scope(|scope| {
+            std::thread::Builder::new()
+                .stack_size(stack_size)
+                .spawn_scoped(scope, || self.evaluate_root(&root, output))
+                .expect("failed to start the thread the script runs on")
+                .join()
+                .expect("the thread the script runs on panicked")
+        })
+    }
+
+    fn evaluate_root(&self, root: &ParserNode, output: Option<String>) -> (Result<Value, RuntimeError>, Option<String>) {
+        let mut runtime = RuntimeState::new(self.limits);
+        runtime.captured_output = output;
+        runtime.add_default_globals_and_functions();
+
+        for (name, builtin) in self.builtins.iter() {
+            runtime.add_builtin(name, builtin.clone());
+        }
+
+        runtime.start_instant = Instant::now();
+        (runtime.evaluate(root), runtime.captured_output)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+mod common;
+
+use common::{display, error, real};
+
+#[test]
+fn sorting_real_numbers() {
+    assert_eq!(display("sort([3, 1, 2])"), "[1, 2, 3]");
+    assert_eq!(display("sortdesc([3, 1, 2])"), "[3, 2, 1]");
+    assert!(error("sort([1, i])").starts_with("cannot order i"));
+}
+
+#[test]
+fn reverse_and_concat() {
+    assert_eq!(display("reverse([1, 2, 3])"), "[3, 2, 1]");
+    assert_eq!(display("concat([1], [2, 3])"), "[1, 2, 3]");
+    assert!(error("concat([1], 2)").starts_with("expected an array as the second argument"));
+}
+
+#[test]
+fn slice_takes_a_half_open_range() {
+    assert_eq!(display("slice([1, 2, 3, 4], 1, 3)"), "[2, 3]");
+    assert_eq!(display("slice([1, 2, 3], 0, 3)"), "[1, 2, 3]");
+    assert_eq!(display("slice([1, 2, 3], 1, 1)"), "[]");
+}
+
+#[test]
+fn slice_checks_its_bounds() {
+    assert!(error("slice([1, 2, 3], 0, 5)").starts_with("cannot slice array of length 3"));
+    assert!(error("slice([1, 2, 3], 2, 1)").contains("start (2) is greater than end (1)"));
+    assert!(error("slice([1, 2, 3], -1, 2)").contains("where start is -1"));
+    assert!(error("slice([1, 2, 3], 0.5, 2)").contains("where start is 0.5"));
+}
+
+#[test]
+fn negative_indices_count_from_the_end() {
+    assert_eq!(real("[10, 20, 30][-1]"), 30.0);
+    assert_eq!(real("[10, 20, 30][-3]"), 10.0);
+    assert!(error("[10, 20, 30][-4]").starts_with("attempted to index array of length 3 with index -4"));
+    assert!(error("[10, 20, 30][3]").starts_with("attempted to index array of length 3 with index 3"));
+    assert!(error("[10, 20, 30][0.5]").starts_with("cannot index arrays with non-integers"));
+}
+
+#[test]
+fn rm_and_ins_accept_negative_indices() {
+    assert_eq!(display("rm([0, 20, 10], -1)"), "[0, 20]");
+    assert_eq!(display("ins([2, 4, 12, 20], 2, i)"), "[2, 4, i, 12, 20]");
+    assert_eq!(display("ins([1, 2, 3], -1, 9)"), "[1, 2, 9, 3]");
+    assert!(error("rm([1, 2], 5)").contains("where y is 5"));
+}
+
+#[test]
+fn map_applies_a_function_to_every_element() {
+    assert_eq!(display("map([1, 4, 9], sqrt)"), "[1, 2, 3]");
+    assert_eq!(display("let square(x) = x^2\nmap([1, 2, 3], square)"), "[1, 4, 9]");
+    assert!(error("map([1, 2], 5)").starts_with("expected a function to map with"));
+    assert!(error("map([1, 2], nothing)").starts_with("unknown variable: nothing"));
+}
+
+#[test]
+fn functions_are_values() {
+    assert_eq!(real("let square(x) = x^2\nlet apply(f, x) = f(x)\napply(square, 3)"), 9.0);
+    assert_eq!(real("let apply(f, x) = f(x)\napply(sin, 0)"), 0.0);
+}
+
+#[test]
+fn filter_keeps_elements_the_predicate_accepts() {
+    assert_eq!(display("let even(x) = x % 2 == 0\nfilter([1, 2, 3, 4], even)"), "[2, 4]");
+    assert_eq!(display("let odd(x) = x % 2\nfilter([1, 2, 3, 4], odd)"), "[1, 3]");
+    assert_eq!(display("let even(x) = x % 2 == 0\nfilter([], even)"), "[]");
+}
+
+#[test]
+fn reduce_folds_from_the_left() {
+    assert_eq!(real("let add(a, b) = a + b\nreduce([1, 2, 3], add, 10)"), 16.0);
+    assert_eq!(real("let add(a, b) = a + b\nreduce([], add, 10)"), 10.0);
+    assert_eq!(real("let sub(a, b) = a - b\nreduce([1, 2, 3], sub, 0)"), -6.0);
+}
+
+#[test]
+fn arrays_are_equal_by_their_elements() {
+    assert_eq!(display("[1, [2, 3]] == [1, [2, 3]]"), "true");
+    assert_eq!(display("[1, 2] == [1, 2, 3]"), "false");
+    assert_eq!(display("[1, 2] == [2, 1]"), "false");
+    assert_eq!(display("[] == []"), "true");
+}
+
+#[test]
+fn arithmetic_between_arrays_is_elementwise() {
+    assert_eq!(display("[1, 2, 3] + [4, 5, 6]"), "[5, 7, 9]");
+    assert_eq!(display("[1, 2, 3] * [4, 5, 6]"), "[4, 10, 18]");
+    assert!(error("[1, 2] + [1, 2, 3]").starts_with("cannot add arrays of different lengths 2 and 3"));
+}
+
+#[test]
+fn arithmetic_broadcasts_over_arrays() {
+    assert_eq!(display("[0, 20, 5.2] * 2"), "[0, 40, 10.4]");
+    assert_eq!(display("1 + [0, 20, 5.2]"), "[1, 21, 6.2]");
+    assert_eq!(display("[1, 2] / 0"), "[inf, inf]");
+}
+
+#[test]
+fn push_appends_a_single_value() {
+    assert_eq!(display("push([1, 2], 3)"), "[1, 2, 3]");
+    assert_eq!(display("append([1, 2], [3])"), "[1, 2, [3]]");
+    assert!(error("push(1, 2)").starts_with("expected an array"));
+}
+
+#[test]
+fn searching_arrays() {
+    assert_eq!(display("contains([[1, 2], 3], [1, 2])"), "true");
+    assert_eq!(display("contains([1, 2], 5)"), "false");
+    assert_eq!(real("indexof([1, 2, 3], 3)"), 2.0);
+    assert_eq!(real("indexof([1, 2, 3], 4)"), -1.0);
+}
+
+#[test]
+fn unique_and_flatten() {
+    assert_eq!(display("unique([1, 2, 1, 3, 2])"), "[1, 2, 3]");
+    assert_eq!(display("flatten([1, [2, [3]]])"), "[1, 2, 3]");
+    assert_eq!(display("flatten([])"), "[]");
+}
+
+#[test]
+fn head_tail_take_and_drop() {
+    assert_eq!(real("head([1, 2, 3])"), 1.0);
+    assert_eq!(display("tail([1, 2, 3])"), "[2, 3]");
+    assert_eq!(display("tail([])"), "[]");
+    assert_eq!(display("take([1, 2, 3], 2)"), "[1, 2]");
+    assert_eq!(display("take([1, 2], 5)"), "[1, 2]");
+    assert_eq!(display("drop([1, 2, 3], 2)"), "[3]");
+    assert_eq!(display("drop([1, 2], 5)"), "[]");
+    assert!(error("head([])").starts_with("cannot find the head of an empty array"));
+    assert!(error("take([1, 2], -1)").starts_with("expected a non-negative integer count"));
+}
+
+#[test]
+fn zip_stops_at_the_shorter_array() {
+    assert_eq!(display("zip([1, 2, 3], [4, 5])"), "[[1, 4], [2, 5]]");
+    assert_eq!(display("zip([], [1])"), "[]");
+}
+
+#[test]
+fn fill_repeats_a_value() {
+    assert_eq!(display("fill(0, 3)"), "[0, 0, 0]");
+    assert_eq!(display("fill([1], 2)"), "[[1], [1]]");
+    assert!(error("fill(0, -1)").starts_with("expected a non-negative integer count"));
+    assert!(error("fill(0, 1.5)").starts_with("expected a non-negative integer count"));
+}
+
+#[test]
+fn dot_product_of_arrays() {
+    assert_eq!(real("dot([1, 2, 3, 4], [5, 6, 7, 8])"), 70.0);
+    assert!(error("dot([1, 2], [1])").starts_with("cannot find dot product of arrays of different lengths 2 and 1"));
+}
+
+#[test]
+fn zipwith_applies_a_function_pairwise() {
+    assert_eq!(display("zipwith([1, 2], [3, 4], fn(a, b) = a * b)"), "[3, 8]");
+    assert_eq!(display("zipwith([1, 2], [3, 4], pow)"), "[1, 16]");
+    assert!(error("zipwith([1, 2], [3], fn(a, b) = a * b)").starts_with("cannot zipwith arrays of different lengths 2 and 1"));
+}
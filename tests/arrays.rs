@@ -0,0 +1,36 @@
+use leibniz::{Limits, Runtime, Value};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[test]
+fn passing_an_array_to_functions_shares_it() {
+    let pointers = Arc::new(Mutex::new(Vec::new()));
+    let recorded = pointers.clone();
+
+    let mut runtime = Runtime::new();
+    runtime.register("remember", 1, move |params, _| {
+        if let Value::Array(array) = &params[0] {
+            recorded.lock().unwrap().push(Arc::as_ptr(array) as usize);
+        }
+
+        Ok(params[0].clone())
+    });
+
+    runtime.run("let big = fill(0, 100000)\nlet pass(a) = remember(a)\nlet twice(a) = pass(pass(a))\nremember(big)\npass(big)\ntwice(big)").unwrap();
+
+    let pointers = pointers.lock().unwrap();
+    assert_eq!(pointers.len(), 4);
+    assert!(pointers.iter().all(|pointer| *pointer == pointers[0]));
+}
+
+#[test]
+fn recursing_with_a_large_array_is_fast() {
+    let mut runtime = Runtime::new();
+    runtime.limits = Limits { timeout: Some(Duration::from_secs(3)), ..Limits::default() };
+
+    let start = Instant::now();
+    let result = runtime.run("let big = fill(1, 1000000)\nlet walk(a, n) = n < 1 => a[-1] | walk(a, n - 1)\nwalk(big, 500)");
+
+    assert_eq!(result, Ok(Value::real(1.0)));
+    assert!(start.elapsed() < Duration::from_secs(3));
+}
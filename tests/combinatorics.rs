@@ -0,0 +1,119 @@
+mod common;
+
+use common::{display, real};
+
+#[test]
+fn factorials_of_small_integers_are_exact() {
+    assert_eq!(real("0!"), 1.0);
+    assert_eq!(real("1!"), 1.0);
+    assert_eq!(real("5!"), 120.0);
+    assert_eq!(real("20!"), 2432902008176640000.0);
+    assert_eq!(real("factorial(10)"), 3628800.0);
+}
+
+#[test]
+fn factorials_past_the_range_of_integers_do_not_overflow() {
+    assert_eq!(real("21!"), 51090942171709440000.0);
+    assert_eq!(real("factorial(22)"), 1124000727777607680000.0);
+    assert!((real("factorial(100)") / 9.332621544394415e157 - 1.0).abs() < 1e-12);
+    assert!((real("170!") / 7.257415615307994e306 - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn factorials_too_big_for_a_float_are_infinite() {
+    assert_eq!(real("171!"), f64::INFINITY);
+    assert_eq!(real("factorial(1e15)"), f64::INFINITY);
+    assert_eq!(display("factorial(1000)"), "inf");
+}
+
+#[test]
+fn factorials_of_other_numbers_use_the_gamma_function() {
+    assert!((real("factorial(0.5)") - 0.886226925452758).abs() < 1e-9);
+    assert!((real("2.5!") - 3.323350970447843).abs() < 1e-9);
+}
+
+#[test]
+fn choose_counts_combinations() {
+    assert_eq!(real("choose(5, 2)"), 10.0);
+    assert_eq!(real("choose(5, 0)"), 1.0);
+    assert_eq!(real("choose(5, 5)"), 1.0);
+    assert_eq!(real("choose(0, 0)"), 1.0);
+}
+
+#[test]
+fn choose_works_past_the_range_of_integer_factorials() {
+    assert_eq!(real("choose(25, 2)"), 300.0);
+    assert_eq!(real("choose(52, 5)"), 2598960.0);
+    assert_eq!(real("choose(1000, 1)"), 1000.0);
+    assert_eq!(real("choose(1000, 999)"), 1000.0);
+    assert_eq!(real("choose(50, 25)"), 126410606437752.0);
+    assert!((real("choose(60, 30)") / 118264581564861424.0 - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn choose_is_infinite_when_too_big_for_a_float() {
+    assert_eq!(real("choose(1e15, 5e14)"), f64::INFINITY);
+}
+
+#[test]
+fn perm_counts_arrangements() {
+    assert_eq!(real("perm(5, 2)"), 20.0);
+    assert_eq!(real("perm(5, 0)"), 1.0);
+    assert_eq!(real("perm(5, 5)"), 120.0);
+}
+
+#[test]
+fn perm_works_past_the_range_of_integer_factorials() {
+    assert_eq!(real("perm(30, 1)"), 30.0);
+    assert_eq!(real("perm(100, 3)"), 970200.0);
+    assert_eq!(real("perm(1e15, 1e15)"), f64::INFINITY);
+}
+
+#[test]
+fn choose_and_perm_reject_bad_arguments() {
+    assert!(common::error("choose(2, 3)").contains("cannot pick 3 out of 2 in choose(n, r)"));
+    assert!(common::error("perm(-1, 0)").contains("expected a non-negative integer in perm(n, r)"));
+    assert!(common::error("choose(2.5, 1)").contains("expected a non-negative integer in choose(n, r)"));
+}
+
+#[test]
+fn gamma_of_integers_is_the_factorial_of_one_less() {
+    assert_eq!(real("gamma(1)"), 1.0);
+    assert_eq!(real("gamma(5)"), 24.0);
+    assert_eq!(real("gamma(22)"), 51090942171709440000.0);
+    assert!((real("gamma(171)") / 7.257415615307994e306 - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn gamma_is_infinite_when_too_big_for_a_float() {
+    assert_eq!(real("gamma(172)"), f64::INFINITY);
+    assert_eq!(real("gamma(1e10)"), f64::INFINITY);
+}
+
+#[test]
+fn gamma_of_large_non_integers_does_not_overflow_early() {
+    assert!((real("gamma(143.5)") / 3.2203704817308093e246 - 1.0).abs() < 1e-12);
+    assert!((real("gamma(170.5)") / 5.56209241456e305 - 1.0).abs() < 1e-12);
+    assert_eq!(real("gamma(171.7)"), f64::INFINITY);
+}
+
+#[test]
+fn gamma_is_nan_at_its_poles() {
+    assert!(real("gamma(0)").is_nan());
+    assert!(real("gamma(-1)").is_nan());
+    assert!(real("factorial(-3)").is_nan());
+    assert!(real("(-3)!").is_nan());
+}
+
+#[test]
+fn gamma_of_other_numbers_is_approximated() {
+    assert!((real("gamma(0.5)") - std::f64::consts::PI.sqrt()).abs() < 1e-9);
+    assert!((real("gamma(-0.5)") + 2.0 * std::f64::consts::PI.sqrt()).abs() < 1e-9);
+}
+
+#[test]
+fn gamma_of_complex_numbers_uses_the_reflection_formula_when_needed() {
+    let product = leibniz::evaluate_str("gamma(0.25 + i) * gamma(0.75 - i)").unwrap().expect_complex("").unwrap();
+    let expected = std::f64::consts::PI / (std::f64::consts::PI * leibniz::Complex64::new(0.25, 1.0)).sin();
+    assert!((product - expected).norm() < 1e-9);
+}
@@ -0,0 +1,27 @@
+// helpers shared by the integration tests. not every test file uses all of them
+#![allow(dead_code)]
+
+use leibniz::Value;
+
+// runs a script on top of the standard library, failing the test if it errors
+pub fn eval(source: &str) -> Value {
+    leibniz::evaluate_str(source).unwrap_or_else(|error| panic!("`{}` failed: {}", source, error))
+}
+
+// runs a script that should evaluate to a real number
+pub fn real(source: &str) -> f64 {
+    eval(source).expect_real("expected a real number").unwrap()
+}
+
+// runs a script and gives back how its result is displayed
+pub fn display(source: &str) -> String {
+    eval(source).to_string()
+}
+
+// runs a script that should fail, giving back its error message
+pub fn error(source: &str) -> String {
+    match leibniz::evaluate_str(source) {
+        Ok(value) => panic!("`{}` should have failed, but evaluated to {}", source, value),
+        Err(error) => error,
+    }
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use common::{display, real};
+
+#[test]
+fn floating_error_in_complex_numbers_is_not_shown() {
+    assert_eq!(display("e^(i * pi)"), "-1");
+    assert_eq!(display("e^(i * pi / 2)"), "i");
+    assert_eq!(display("polar(2, pi)"), "-2");
+}
+
+#[test]
+fn hiding_floating_error_does_not_change_the_value() {
+    assert!(real("Im(e^(i * pi))") > 0.0);
+    assert!(real("Im(e^(i * pi))") < 1e-15);
+}
+
+#[test]
+fn parts_that_are_not_tiny_are_always_shown() {
+    assert_eq!(display("1e20 + i"), "100000000000000000000 + 1i");
+    assert_eq!(display("1e-10 + 1e10i"), "0.0000000001 + 10000000000i");
+}
+
+#[test]
+fn numbers_made_of_tiny_parts_are_shown_as_they_are() {
+    assert_eq!(display("1e-20"), "0.00000000000000000001");
+    assert_eq!(display("1e-20i"), "0.00000000000000000001i");
+    assert_eq!(display("1e-20 + 1e-20i"), "0.00000000000000000001 + 0.00000000000000000001i");
+}
@@ -0,0 +1,95 @@
+use leibniz::{ErrorKind, Limits, Runtime, RuntimeError};
+use std::time::Duration;
+
+// runs a script without the standard library, with the given limits, and gives back its error
+fn execute_error(source: &str, limits: Limits) -> RuntimeError {
+    let root = leibniz::parser::parse_leibniz_file(source).unwrap();
+    let mut runtime = Runtime::new();
+    runtime.limits = limits;
+    runtime.execute(root).unwrap_err()
+}
+
+fn kind(source: &str) -> ErrorKind {
+    execute_error(source, Limits::default()).kind
+}
+
+#[test]
+fn errors_keep_their_kind() {
+    assert_eq!(kind("nothing"), ErrorKind::UndefinedVariable("nothing".to_string()));
+    assert_eq!(kind("nothing(1)"), ErrorKind::UndefinedFunction("nothing".to_string()));
+    assert_eq!(kind("sin(1, 2)"), ErrorKind::ArityMismatch("sin".to_string(), 1, 2));
+    assert_eq!(kind("[1, 2][5]"), ErrorKind::IndexOutOfBounds(5.0, 2));
+    assert_eq!(kind("frac(1, 0)"), ErrorKind::DivisionByZero);
+    assert!(matches!(kind("vec(1, 2) + 1"), ErrorKind::TypeMismatch(_)));
+    assert_eq!(kind("error(\"bad input\")"), ErrorKind::Other("bad input".to_string()));
+}
+
+#[test]
+fn limit_errors_keep_their_kind() {
+    let recursion = Limits { recursion: 10, ..Limits::default() };
+    assert_eq!(execute_error("let f(x) = f(x)\nf(1)", recursion).kind, ErrorKind::RecursionLimitExceeded(10));
+
+    let steps = Limits { steps: Some(100), ..Limits::default() };
+    assert_eq!(execute_error("while true => 1", steps).kind, ErrorKind::BudgetExceeded(100));
+
+    let timeout = Limits { timeout: Some(Duration::from_millis(10)), ..Limits::default() };
+    assert_eq!(execute_error("while true => 1", timeout).kind, ErrorKind::Timeout(Duration::from_millis(10)));
+}
+
+#[test]
+fn errors_report_where_they_happened() {
+    let error = execute_error("let x = 1\nlet y = 2\nx + nothing", Limits::default());
+    assert_eq!(error.location, Some((3, 5)));
+    assert_eq!(error.to_string(), "unknown variable: nothing at line 3, column 5");
+}
+
+#[test]
+fn errors_inside_functions_point_at_the_function_body() {
+    let error = execute_error("let f(x) = x + nothing\nf(1)", Limits::default());
+    assert_eq!(error.location, Some((1, 16)));
+}
+
+#[test]
+fn errors_from_statements_point_at_the_statement() {
+    let error = execute_error("let a = 1\nlet f(x) = {\n  a = x\n  a\n}\nf(2)", Limits::default());
+    assert!(error.to_string().starts_with("attempted to affect external variable a"));
+    assert_eq!(error.location, Some((3, 3)));
+
+    let error = execute_error("let a = 0\nlet b = 0\na, b = [1, 2, 3]", Limits::default());
+    assert!(error.to_string().starts_with("cannot unpack an array of 3 elements into 2 variables"));
+    assert_eq!(error.location, Some((3, 1)));
+
+    let error = execute_error("let a = 1\nlet a = 2\na", Limits::default());
+    assert!(error.to_string().starts_with("you cannot redeclare a variable: a"));
+    assert_eq!(error.location, Some((2, 1)));
+}
+
+#[test]
+fn limit_errors_are_located_like_any_other_error() {
+    let timeout = Limits { timeout: Some(Duration::from_millis(10)), ..Limits::default() };
+    let error = execute_error("let spin(x) = { while true => x }\nspin(1)", timeout);
+    assert_eq!(error.location, Some((1, 17)));
+
+    let recursion = Limits { recursion: 10, ..Limits::default() };
+    assert!(execute_error("let f(x) = f(x)\nf(1)", recursion).location.is_some());
+}
+
+#[test]
+fn the_kind_displays_without_the_location() {
+    let error = execute_error("error(\"bad input\")", Limits::default());
+    assert_eq!(error.kind.to_string(), "bad input");
+}
+
+#[test]
+fn error_raises_exactly_the_message_it_is_given() {
+    let error = execute_error("let check(x) = x < 0 => error(\"bad input\") | x\ncheck(1)\ncheck(-1)", Limits::default());
+
+    assert_eq!(error.kind, ErrorKind::Other("bad input".to_string()));
+    assert_eq!(error.kind.to_string(), "bad input");
+    assert_eq!(error.location, Some((1, 25)));
+}
+
+#[test]
+fn error_expects_a_string() {
+    assert!(matches!(kind("error(1)"), ErrorKind::TypeMismatch(_)));
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::{display, error, real};
+
+#[test]
+fn fractions_stay_exact() {
+    assert_eq!(display("frac(1, 2) + frac(1, 3)"), "5/6");
+    assert_eq!(display("frac(2, -4)"), "-1/2");
+    assert_eq!(display("frac(1, 2) * 2 == 1"), "true");
+    assert_eq!(display("typeof(frac(1, 3))"), "fraction");
+}
+
+#[test]
+fn fractions_compare_after_arithmetic() {
+    assert_eq!(display("frac(1, 2) + frac(1, 3) == frac(5, 6)"), "true");
+    assert_eq!(display("frac(1, 2) + frac(1, 3) != frac(5, 6)"), "false");
+    assert_eq!(display("frac(1, 3) < frac(1, 2)"), "true");
+}
+
+#[test]
+fn fractions_mixed_with_other_numbers_become_numbers() {
+    assert_eq!(real("frac(1, 2) + 0.25"), 0.75);
+}
+
+#[test]
+fn fractions_need_integers_and_a_nonzero_denominator() {
+    assert!(error("frac(0.5, 2)").starts_with("expected an integer numerator"));
+    assert!(error("frac(1, 0)").starts_with("cannot divide by zero"));
+    assert!(error("frac(1e19, 1)").starts_with("expected an integer numerator"));
+    assert!(error("frac(1, 2^63)").starts_with("expected an integer denominator"));
+}
+
+#[test]
+fn dividing_a_fraction_by_zero_gives_inf() {
+    assert_eq!(real("frac(1, 2) / 0"), f64::INFINITY);
+    assert!(real("frac(0, 1) / 0").is_nan());
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::{display, error, real};
+
+#[test]
+fn functions_can_recurse() {
+    assert_eq!(real("let fact(x) = x < 1 => 1 | x * fact(x - 1)\nfact(5)"), 120.0);
+}
+
+#[test]
+fn calls_check_the_function_and_its_arguments() {
+    assert_eq!(error("undefined_fn(1)"), "unknown function: undefined_fn at line 1, column 1");
+    assert!(error("let f = 5\nf(1)").starts_with("unknown function: f"));
+    assert!(error("let f(x) = x\nf(1, 2)").starts_with("f expects 1 parameters, but 2 were supplied"));
+    assert!(error("let f(x) = x\nlet f(x) = 2\n1").starts_with("redeclared a function that already is defined: f"));
+}
+
+#[test]
+fn global_assigns_from_within_functions() {
+    assert_eq!(real("let count = 0\nlet tick(n) = {\nglobal count = count + n\ncount\n}\ntick(2)\ntick(3)\ncount"), 5.0);
+    assert!(error("let f(x) = { global nope = x\n1 }\nf(1)").starts_with("unknown variable: nope"));
+}
+
+#[test]
+fn functions_cannot_assign_to_outside_variables_without_global() {
+    assert!(error("let count = 0\nlet tick(n) = { count = n\n1 }\ntick(2)").starts_with("attempted to affect external variable count from within a function"));
+}
+
+#[test]
+fn lambdas_can_be_stored_and_called() {
+    assert_eq!(real("let cube = fn(x) = x^3\ncube(2)"), 8.0);
+    assert_eq!(real("let add = fn(x, y) = { let z = x + y\nz }\nadd(1, 2)"), 3.0);
+    assert_eq!(display("map([1, 2, 3], fn(x) = x * 2)"), "[2, 4, 6]");
+    assert!(error("map([1, 2], fn(a, b) = a)").contains("expects 2 parameters, but 1 were supplied"));
+}
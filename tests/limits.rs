@@ -0,0 +1,94 @@
+use leibniz::{ErrorKind, Limits, Runtime, Value};
+
+fn runtime(limits: Limits) -> Runtime {
+    let mut runtime = Runtime::new();
+    runtime.limits = limits;
+    runtime
+}
+
+#[test]
+fn scripts_can_run_on_the_calling_thread() {
+    let runtime = runtime(Limits { stack_size: None, recursion: 50, ..Limits::default() });
+
+    for n in 0..100 {
+        assert_eq!(runtime.run(&format!("{} + 1", n)), Ok(Value::real(n as f64 + 1.0)));
+    }
+}
+
+#[test]
+fn the_recursion_limit_still_applies_on_the_calling_thread() {
+    let runtime = runtime(Limits { stack_size: None, recursion: 50, ..Limits::default() });
+    let root = leibniz::parser::parse_leibniz_file("let f(x) = f(x)\nf(1)").unwrap();
+
+    assert_eq!(runtime.execute(root).unwrap_err().kind, ErrorKind::RecursionLimitExceeded(50));
+}
+
+#[test]
+fn the_default_stack_fits_the_default_recursion_limit() {
+    let runtime = Runtime::new();
+    let root = leibniz::parser::parse_leibniz_file("let f(x) = f(x + 1)\nf(1)").unwrap();
+
+    assert_eq!(runtime.execute(root).unwrap_err().kind, ErrorKind::RecursionLimitExceeded(1000));
+}
+
+#[test]
+fn the_default_stack_fits_nested_function_bodies() {
+    let runtime = Runtime::new();
+    let root = leibniz::parser::parse_leibniz_file("let f(x) = 1 + (1 + (1 + (1 + (1 + (1 + f(x + 1))))))\nf(1)").unwrap();
+
+    assert_eq!(runtime.execute(root).unwrap_err().kind, ErrorKind::RecursionLimitExceeded(1000));
+}
+
+#[test]
+fn a_smaller_stack_can_be_chosen() {
+    let runtime = runtime(Limits { stack_size: Some(8 * 1024 * 1024), recursion: 100, ..Limits::default() });
+    assert_eq!(runtime.run("let f(x) = x < 1 => 0 | 1 + f(x - 1)\nf(50)"), Ok(Value::real(50.0)));
+}
+
+fn run_error(runtime: &Runtime, source: &str) -> ErrorKind {
+    let root = leibniz::parser::parse_leibniz_file(source).unwrap();
+    runtime.execute(root).unwrap_err().kind
+}
+
+#[test]
+fn huge_arrays_and_matrices_are_rejected() {
+    let runtime = Runtime::new();
+
+    for source in ["fill(0, 1e12)", "identity(1e6)", "zeros(1e10, 1e10)", "range(0, 1e12, 1)"].iter() {
+        assert_eq!(run_error(&runtime, source), ErrorKind::ElementLimitExceeded(10_000_000), "{}", source);
+    }
+}
+
+#[test]
+fn the_element_limit_can_be_changed() {
+    let runtime = runtime(Limits { elements: Some(10), ..Limits::default() });
+
+    assert_eq!(runtime.run("len(fill(0, 10))"), Ok(Value::real(10.0)));
+    assert_eq!(run_error(&runtime, "fill(0, 11)"), ErrorKind::ElementLimitExceeded(10));
+    assert_eq!(run_error(&runtime, "identity(4)"), ErrorKind::ElementLimitExceeded(10));
+    assert_eq!(runtime.run("len(range(0, 9, 1))"), Ok(Value::real(10.0)));
+    assert_eq!(run_error(&runtime, "range(0, 10, 1)"), ErrorKind::ElementLimitExceeded(10));
+}
+
+#[test]
+fn growing_arrays_are_limited_too() {
+    let runtime = runtime(Limits { elements: Some(10), ..Limits::default() });
+
+    assert_eq!(runtime.run("len(concat(fill(0, 5), fill(0, 5)))"), Ok(Value::real(10.0)));
+    assert_eq!(run_error(&runtime, "let a = fill(0, 8)\na = concat(a, a)"), ErrorKind::ElementLimitExceeded(10));
+    assert_eq!(run_error(&runtime, "push(fill(0, 10), 1)"), ErrorKind::ElementLimitExceeded(10));
+    assert_eq!(run_error(&runtime, "append(fill(0, 10), 1)"), ErrorKind::ElementLimitExceeded(10));
+    assert_eq!(run_error(&runtime, "ins(fill(0, 10), 0, 1)"), ErrorKind::ElementLimitExceeded(10));
+    assert_eq!(run_error(&runtime, "flatten(fill(fill(0, 4), 3))"), ErrorKind::ElementLimitExceeded(10));
+    assert_eq!(run_error(&runtime, "chars(\"abcdefghijk\")"), ErrorKind::ElementLimitExceeded(10));
+    assert_eq!(run_error(&runtime, "x: collect [1..11, 1] => x"), ErrorKind::ElementLimitExceeded(10));
+}
+
+#[test]
+fn sizes_must_be_non_negative_integers() {
+    let runtime = Runtime::new();
+
+    for source in ["fill(0, inf)", "fill(0, inf * 0)", "fill(0, -1)", "fill(0, 1.5)", "identity(0)", "identity(inf)", "zeros(2, 0.5)"].iter() {
+        assert!(matches!(run_error(&runtime, source), ErrorKind::TypeMismatch(_)), "{}", source);
+    }
+}
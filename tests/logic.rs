@@ -0,0 +1,96 @@
+mod common;
+
+use common::{display, error, real};
+
+#[test]
+fn comparisons_give_booleans() {
+    assert_eq!(display("5 < 9"), "true");
+    assert_eq!(display("9 < 5"), "false");
+    assert_eq!(display("true"), "true");
+    assert_eq!(real("true => 1 | 2"), 1.0);
+}
+
+#[test]
+fn logical_functions() {
+    assert_eq!(display("not(true)"), "false");
+    assert_eq!(display("and(true, 1)"), "true");
+    assert_eq!(display("or(false, 0)"), "false");
+    assert_eq!(display("let x = 5\nand(x > 2, x < 10)"), "true");
+    assert!(error("and(1, \"a\")").starts_with("expected a boolean or a real number in and(a, b)"));
+}
+
+#[test]
+fn ordering_compares_signed_real_numbers() {
+    assert_eq!(display("-5 < 3"), "true");
+    assert_eq!(display("-5 > 3"), "false");
+    assert_eq!(display("-3 > -5"), "true");
+    assert_eq!(display("-2 <= -2"), "true");
+    assert_eq!(display("5 >= 7"), "false");
+}
+
+#[test]
+fn ordering_rejects_complex_numbers_and_other_values() {
+    assert!(error("i < 1").starts_with("cannot order i, ordering is undefined for complex values"));
+    assert!(error("\"a\" < \"b\"").starts_with("cannot compare less-than between a string and a string"));
+    assert!(error("1 < 2 < 3").starts_with("cannot compare less-than between a boolean and a number"));
+}
+
+#[test]
+fn typeof_names_every_kind_of_value() {
+    assert_eq!(display("typeof(1)"), "number");
+    assert_eq!(display("typeof(frac(1, 2))"), "fraction");
+    assert_eq!(display("typeof(vec(1, 2))"), "vector");
+    assert_eq!(display("typeof(identity(2))"), "matrix");
+    assert_eq!(display("typeof([1])"), "array");
+    assert_eq!(display("typeof(true)"), "boolean");
+    assert_eq!(display("typeof(\"a\")"), "string");
+    assert_eq!(display("typeof(sin)"), "function");
+    assert_eq!(display("typeof(fn(x) = x)"), "function");
+}
+
+#[test]
+fn assert_passes_its_condition_through() {
+    assert_eq!(display("assert(1 == 1, \"fine\")"), "true");
+    assert_eq!(error("assert(1 == 2, \"numbers differ\")"), "assertion failed: numbers differ at line 1, column 1");
+    assert!(error("assert(0, \"zero\")").starts_with("assertion failed: zero"));
+    assert!(error("assert(\"a\", \"b\")").starts_with("expected a boolean or real number as the condition"));
+}
+
+#[test]
+fn error_stops_the_script_with_a_message() {
+    assert_eq!(error("error(\"stop here\")"), "stop here at line 1, column 1");
+    assert!(error("error(5)").starts_with("expected a string as the message"));
+}
+
+#[test]
+fn piecewise_picks_the_first_condition_that_holds() {
+    assert_eq!(real("let x = 5\npiecewise(x < 0, -1, x < 10, 1, 2)"), 1.0);
+    assert_eq!(real("let x = 5\npiecewise(x < 0, -1, 0)"), 0.0);
+    assert!(error("piecewise(1, 2)").starts_with("expected condition and value pairs followed by a default value"));
+    assert!(error("piecewise(i, 1, 2)").starts_with("expected a boolean or a real number as a condition"));
+}
+
+#[test]
+fn conditionals_accept_booleans_and_real_numbers() {
+    assert_eq!(real("true => 1 | 2"), 1.0);
+    assert_eq!(real("0 => 1 | 2"), 2.0);
+    assert_eq!(display("5 > 3 => \"big\" | \"small\""), "big");
+    assert_eq!(real("let x = 1\nlet y = x > 2 => 20 | x + 5\ny"), 6.0);
+}
+
+#[test]
+fn conditionals_reject_other_predicates() {
+    assert!(error("i => 1 | 2").contains("must be a boolean or a real number, not a complex number"));
+    assert!(error("\"a\" => 1 | 2").contains("must be a boolean or a real number, not a string"));
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    assert_eq!(display("true && false"), "false");
+    assert_eq!(display("true || false"), "true");
+    assert_eq!(display("1 && 0"), "false");
+    assert_eq!(display("false && nothing"), "false");
+    assert_eq!(display("true || nothing"), "true");
+    assert!(error("\"a\" && true").starts_with("expected a boolean or a real number on either side of &&"));
+    assert!(error("true && nothing").starts_with("unknown variable: nothing"));
+}
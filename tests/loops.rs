@@ -0,0 +1,54 @@
+mod common;
+
+use common::{display, error, real};
+
+#[test]
+fn loops_over_arrays_sum_their_body() {
+    assert_eq!(real("let a = [1, 2, 3]\nz: a => z * 2"), 12.0);
+    assert_eq!(display("z: [vec(1, 2), vec(3, 4)] => z"), "(4, 6)");
+    assert_eq!(real("z: [] => z"), 0.0);
+    assert!(error("z: 5 => z").starts_with("cannot loop through a number, only ranges and arrays"));
+}
+
+#[test]
+fn while_loops_run_until_the_predicate_fails() {
+    assert_eq!(real("let n = 1\nwhile n < 100 => { n = n * 2 }"), 128.0);
+    assert_eq!(real("while false => 5"), 0.0);
+    assert!(error("while \"a\" => 5").contains("must be a boolean or a real number"));
+}
+
+#[test]
+fn loop_modes_combine_results() {
+    assert_eq!(real("x: [1..4, 1] => x"), 10.0);
+    assert_eq!(real("x: sum [1..4, 1] => x"), 10.0);
+    assert_eq!(real("x: product [1..4, 1] => x"), 24.0);
+    assert_eq!(real("x: last [1..4, 1] => x"), 4.0);
+    assert_eq!(real("z: product [1, 2, 3, 4] => z"), 24.0);
+    assert_eq!(real("z: last [1, 2, 3] => z"), 3.0);
+    assert_eq!(real("z: product [] => z"), 1.0);
+}
+
+#[test]
+fn mode_words_can_still_be_used_as_names() {
+    assert_eq!(real("let last = 3\nlast + 1"), 4.0);
+}
+
+#[test]
+fn mode_words_followed_by_parentheses_call_builtins() {
+    assert_eq!(real("x: sum([[1, 2], [3, 4]]) => x * 10"), 100.0);
+    assert_eq!(display("x: collect sum([[1, 2], [3, 4]]) => x * 10"), "[40, 60]");
+    assert!(error("x: product([2, 3]) => x").starts_with("cannot loop through a number"));
+}
+
+#[test]
+fn collect_gathers_every_result() {
+    assert_eq!(display("x: collect [1..3, 1] => x^2"), "[1, 4, 9]");
+    assert_eq!(display("z: collect [1, 2, 3] => z * 2"), "[2, 4, 6]");
+    assert_eq!(display("z: collect [] => z"), "[]");
+}
+
+#[test]
+fn collect_includes_the_second_bound_exactly_once() {
+    assert_eq!(display("p: collect [0..10, 3] => p"), "[0, 3, 6, 9, 10]");
+    assert_eq!(display("x: collect [0..0.3, 0.1] => x"), "[0, 0.1, 0.2, 0.3]");
+}
@@ -0,0 +1,222 @@
+mod common;
+
+use common::{display, error, real};
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-12
+}
+
+#[test]
+fn square_roots_take_the_principal_branch() {
+    assert_eq!(display("sqrt(-4)"), "2i");
+    assert_eq!(display("sqrt(vec(4, 9))"), "(2, 3)");
+    assert!(error("sqrt(\"a\")").starts_with("expected a number or vector to find square root of"));
+}
+
+#[test]
+fn abs_finds_magnitudes() {
+    assert_eq!(real("abs(-3)"), 3.0);
+    assert_eq!(real("abs(vec(3, 4))"), 5.0);
+    assert_eq!(display("abs([-1, 3 - 4i])"), "[1, 5]");
+    assert!(error("abs(true)").starts_with("cannot find magnitude of a boolean"));
+}
+
+#[test]
+fn rounding_works_on_both_parts() {
+    assert_eq!(display("floor(2.7 + 3.2i)"), "2 + 3i");
+    assert_eq!(display("ceil(2.1 - 3.5i)"), "3 - 3i");
+    assert_eq!(real("round(-2.5)"), -3.0);
+    assert_eq!(real("trunc(-2.7)"), -2.0);
+    assert!(error("floor(\"a\")").starts_with("expected a number to find floor of"));
+}
+
+#[test]
+fn min_and_max_of_arrays() {
+    assert_eq!(real("min([3, 1, 2])"), 1.0);
+    assert_eq!(real("max([3, 1, 2])"), 3.0);
+    assert!(error("min([])").starts_with("cannot find minimum of an empty array"));
+    assert!(error("max([1, i])").starts_with("cannot order i"));
+}
+
+#[test]
+fn min_and_max_of_several_numbers() {
+    assert_eq!(real("max(3, 7, 2)"), 7.0);
+    assert_eq!(real("min(4, -1, 2)"), -1.0);
+}
+
+#[test]
+fn clamp_keeps_numbers_between_bounds() {
+    assert_eq!(real("clamp(5, 0, 3)"), 3.0);
+    assert_eq!(real("clamp(-1, 0, 3)"), 0.0);
+    assert_eq!(real("clamp(2, 0, 3)"), 2.0);
+    assert!(error("clamp(1, 3, 0)").contains("the lower bound is greater than the upper bound"));
+}
+
+#[test]
+fn inverse_trig() {
+    assert!(close(real("asin(1)"), FRAC_PI_2));
+    assert_eq!(real("acos(1)"), 0.0);
+    assert!(close(real("atan(1)"), FRAC_PI_4));
+    assert_eq!(display("asin(2)"), "1.5707963267948966 - 1.3169578969248166i");
+}
+
+#[test]
+fn hyperbolic_trig() {
+    assert!(close(real("sinh(1)"), 1f64.sinh()));
+    assert_eq!(real("cosh(0)"), 1.0);
+    assert_eq!(real("sinh(0) + tanh(0) + asinh(0) + acosh(1) + atanh(0)"), 0.0);
+}
+
+#[test]
+fn atan2_accounts_for_the_quadrant() {
+    assert_eq!(real("atan2(0, 1)"), 0.0);
+    assert!(close(real("atan2(1, -1)"), 3.0 * FRAC_PI_4));
+    assert!(close(real("atan2(-1, -1)"), -3.0 * FRAC_PI_4));
+    assert!(error("atan2(i, 1)").starts_with("expected a real y coordinate"));
+}
+
+#[test]
+fn arg_and_modulus() {
+    assert!(close(real("arg(i)"), FRAC_PI_2));
+    assert!(close(real("phase(-1)"), PI));
+    assert_eq!(real("modulus(3-4i)"), 5.0);
+}
+
+#[test]
+fn polar_form_round_trips() {
+    assert_eq!(display("to_polar(2i)"), "[2, 1.5707963267948966]");
+    assert_eq!(display("polar(2, pi)"), "-2");
+    assert_eq!(display("polar(1, pi / 2)"), "i");
+}
+
+#[test]
+fn reductions_over_arrays() {
+    assert_eq!(real("sum([1, 2, 3])"), 6.0);
+    assert_eq!(real("product([1, 2, 3, 4])"), 24.0);
+    assert_eq!(real("mean([1, 2, 3, 4])"), 2.5);
+    assert_eq!(real("sum([])"), 0.0);
+    assert_eq!(real("product([])"), 1.0);
+    assert!(error("mean([])").starts_with("cannot find mean of an empty array"));
+}
+
+#[test]
+fn lerp_between_numbers_and_vectors() {
+    assert_eq!(real("lerp(0, 10, 2)"), 20.0);
+    assert_eq!(real("lerp(0, 10, 0.5)"), 5.0);
+    assert_eq!(display("lerp(vec(0, 0), vec(2, 4), 0.5)"), "(1, 2)");
+    assert!(error("lerp(0, vec(1, 1), 0.5)").starts_with("cannot subtract a vector from a number"));
+}
+
+#[test]
+fn pow_matches_the_power_operator() {
+    assert_eq!(real("pow(2, 10)"), 1024.0);
+    assert_eq!(display("pow(-1, 0.5)"), display("(-1)^0.5"));
+    assert_eq!(display("pow(vec(1, 2), 2)"), "(1, 4)");
+}
+
+#[test]
+fn gcd_and_lcm_of_integers() {
+    assert_eq!(real("gcd(12, 18)"), 6.0);
+    assert_eq!(real("gcd(-12, 18)"), 6.0);
+    assert_eq!(real("lcm(4, 6)"), 12.0);
+    assert_eq!(real("gcd(0, 0)"), 0.0);
+    assert_eq!(real("lcm(3, 0)"), 0.0);
+    assert!(error("gcd(1.5, 2)").starts_with("expected an integer"));
+    assert!(error("gcd(1e19, 2)").starts_with("expected an integer"));
+    assert!(error("lcm(2, -1e19)").starts_with("expected an integer"));
+}
+
+#[test]
+fn sign_of_real_numbers() {
+    assert_eq!(real("sign(-3)"), -1.0);
+    assert_eq!(real("sign(0)"), 0.0);
+    assert_eq!(real("sign(2)"), 1.0);
+    assert!(error("sign(i)").starts_with("expected a real number"));
+}
+
+#[test]
+fn angle_conversions() {
+    assert!(close(real("deg(pi)"), 180.0));
+    assert!(close(real("rad(180)"), PI));
+    assert!(close(real("sin(rad(90))"), 1.0));
+}
+
+#[test]
+fn exp_is_the_complex_exponential() {
+    assert_eq!(real("exp(0)"), 1.0);
+    assert!(close(real("exp(1)"), std::f64::consts::E));
+    assert_eq!(display("exp(i * pi)"), "-1");
+}
+
+#[test]
+fn roots_gives_every_root() {
+    assert_eq!(display("roots(-4, 2)"), "[2i, -2i]");
+    assert_eq!(real("len(roots(1, 5))"), 5.0);
+    assert_eq!(display("roots(1, 3)[0]"), "1");
+    assert!(error("roots(1, 0)").starts_with("expected a positive integer n"));
+}
+
+#[test]
+fn statistics() {
+    assert_eq!(real("median([3, 1, 2])"), 2.0);
+    assert_eq!(real("median([4, 1, 3, 2])"), 2.5);
+    assert_eq!(real("variance([1, 2, 3, 4])"), 1.25);
+    assert_eq!(real("stddev([2, 4, 4, 4, 5, 5, 7, 9])"), 2.0);
+    assert!(error("median([])").starts_with("cannot find median of an empty array"));
+    assert!(error("variance([1, i])").starts_with("expected an array of real numbers"));
+}
+
+#[test]
+fn mod_follows_the_sign_of_the_divisor() {
+    assert_eq!(real("mod(-1, 3)"), 2.0);
+    assert_eq!(real("mod(5, -3)"), -1.0);
+    assert_eq!(real("-1 % 3"), -1.0);
+    assert!(error("mod(1, 0)").starts_with("cannot divide by zero"));
+    assert!(error("mod(i, 2)").starts_with("expected a real dividend"));
+}
+
+#[test]
+fn remainders_reject_complex_numbers() {
+    assert_eq!(real("7 % 4"), 3.0);
+    assert!(error("(1 + i) % 2").contains("remainders only work on real numbers"));
+    assert!(error("2 % i").contains("remainders only work on real numbers"));
+}
+
+#[test]
+fn cumsum_gives_running_totals() {
+    assert_eq!(display("cumsum([1, 2, 3])"), "[1, 3, 6]");
+    assert_eq!(display("cumsum([])"), "[]");
+}
+
+#[test]
+fn divmod_rounds_down() {
+    assert_eq!(display("divmod(-7, 3)"), "[-3, 2]");
+    assert_eq!(display("divmod(7, 2)"), "[3, 1]");
+    assert!(error("divmod(7, 0)").starts_with("cannot divide by zero"));
+}
+
+#[test]
+fn constants() {
+    assert!(close(real("tau"), 2.0 * PI));
+    assert!(close(real("phi"), (1.0 + 5f64.sqrt()) / 2.0));
+    assert_eq!(real("inf"), f64::INFINITY);
+    assert_eq!(real("-inf"), f64::NEG_INFINITY);
+}
+
+#[test]
+fn float_classification() {
+    assert_eq!(display("isnan(0 / 0)"), "true");
+    assert_eq!(display("isinf(1 / 0)"), "true");
+    assert_eq!(display("isnan(1 / 0)"), "false");
+    assert_eq!(display("isfinite(1)"), "true");
+    assert_eq!(display("isfinite(1 / 0)"), "false");
+    assert!(error("isnan(vec(1, 2))").starts_with("expected a number"));
+}
+
+#[test]
+fn complex_builds_numbers_from_parts() {
+    assert_eq!(display("let x = 3\ncomplex(x, x + 1)"), "3 + 4i");
+    assert_eq!(display("complex(0, 1)"), "i");
+    assert!(error("complex(i, 1)").starts_with("expected a real number as the real part"));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::{display, real};
+
+#[test]
+fn negation_binds_to_its_operand() {
+    assert_eq!(real("let x = 2\nlet y = -x + 1\ny"), -1.0);
+    assert_eq!(display("let x = 2\nlet y = -x < 0\ny"), "true");
+    assert_eq!(real("let x = 2\n2 * -x"), -4.0);
+    assert_eq!(real("let x = 2\nlet y = -x^2\ny"), -4.0);
+    assert_eq!(real("let x = 2\nlet y = --x\ny"), 2.0);
+}
+
+#[test]
+fn negation_of_compound_values() {
+    assert_eq!(display("-(3 - 4i)"), "-3 + 4i");
+    assert_eq!(display("-[1, 2i, vec(1, 2)]"), "[-1, -2i, (-1, -2)]");
+}
+
+#[test]
+fn arithmetic_folds_from_the_left() {
+    assert_eq!(real("8 / 2 / 2"), 2.0);
+    assert_eq!(real("7 % 4 * 2"), 6.0);
+    assert_eq!(real("10 - 2 - 3"), 5.0);
+}
+
+#[test]
+fn comparisons_bind_looser_than_arithmetic() {
+    assert_eq!(display("1 + 1 == 2"), "true");
+    assert_eq!(display("2 * 3 >= 6"), "true");
+    assert_eq!(display("1 < 2 * 3"), "true");
+    assert_eq!(display("2 * 3 >= 6 => 1 | 0"), "1");
+}
+
+#[test]
+fn not_equals_binds_looser_than_arithmetic() {
+    assert_eq!(display("let a = 1\nlet b = 2\na + 1 != b"), "false");
+    assert_eq!(display("let a = 1\nlet b = 3\na + 1 != b"), "true");
+    assert_eq!(display("2 * 3 != 6"), "false");
+    assert_eq!(display("[1, 2] != [1, 2]"), "false");
+}
+
+#[test]
+fn comparisons_bind_tighter_than_logical_operators() {
+    assert_eq!(display("let x = 1\nx != 0 && 1/x > 2"), "false");
+    assert_eq!(display("let x = 0\nx != 0 && 1/x > 2"), "false");
+    assert_eq!(display("let x = 1\n(x == 0) || (1 / x > 2)"), "false");
+    assert_eq!(display("let x = 0\n(x == 0) || (1 / x > 2)"), "true");
+    assert_eq!(display("let x = 5\nx > 2 && x < 10"), "true");
+}
+
+#[test]
+fn powers_bind_tighter_than_minus_on_number_literals() {
+    assert_eq!(real("-2^2"), -4.0);
+    assert_eq!(real("-1^0.5"), -1.0);
+    assert_eq!(display("(-1)^0.5"), "i");
+    assert_eq!(real("2^-1"), 0.5);
+    assert_eq!(real("1 - -1"), 2.0);
+}
@@ -0,0 +1,36 @@
+use leibniz::{ErrorKind, Runtime, Value};
+
+#[test]
+fn printed_lines_are_captured() {
+    let root = leibniz::parser::parse_leibniz_file("print(1)\nprint(\"two\")\n3").unwrap();
+    let (result, output) = Runtime::new().execute_capturing(root);
+
+    assert_eq!(result, Ok(Value::real(3.0)));
+    assert_eq!(output, "1\ntwo\n");
+}
+
+#[test]
+fn output_is_kept_when_the_script_fails() {
+    let root = leibniz::parser::parse_leibniz_file("print(\"before\")\nerror(\"stop\")\nprint(\"after\")").unwrap();
+    let (result, output) = leibniz::runtime::execute_capturing(root);
+
+    assert_eq!(result.unwrap_err().kind, ErrorKind::Other("stop".to_string()));
+    assert_eq!(output, "before\n");
+}
+
+#[test]
+fn set_precision_changes_printed_numbers() {
+    let root = leibniz::parser::parse_leibniz_file("print(1 / 3)\nset_precision(3)\nprint(1 / 3)\nprint(format(\"{}\", 2 / 3))\nset_precision(0)\nprint(1 / 3)").unwrap();
+    let (_, output) = Runtime::new().execute_capturing(root);
+
+    assert_eq!(output, "0.3333333333333333\n0.333\n0.667\n0.3333333333333333\n");
+}
+
+#[test]
+fn set_precision_rejects_negative_digits() {
+    let root = leibniz::parser::parse_leibniz_file("set_precision(-1)").unwrap();
+    let (result, _) = Runtime::new().execute_capturing(root);
+
+    let expected = "expected a non-negative integer number of digits in set_precision(digits)";
+    assert_eq!(result.unwrap_err().kind, ErrorKind::TypeMismatch(expected.to_string()));
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::{display, error};
+
+#[test]
+fn range_builtin_includes_both_bounds() {
+    assert_eq!(display("range(0, 10, 3)"), "[0, 3, 6, 9, 10]");
+    assert_eq!(display("range(3, 0, 1)"), "[3, 2, 1, 0]");
+    assert_eq!(display("range(3, 3, 1)"), "[3]");
+}
+
+#[test]
+fn range_builtin_rejects_a_zero_step() {
+    assert!(error("range(0, 1, 0)").contains("a step cannot be 0"));
+}
+
+#[test]
+fn range_builtin_rejects_infinite_bounds() {
+    assert!(error("range(0, inf, 1)").contains("the bounds of a range must be finite"));
+    assert!(error("range(-inf, 0, 1)").contains("the bounds of a range must be finite"));
+}
+
+#[test]
+fn range_builtin_rejects_nan_bounds() {
+    assert!(error("range(inf * 0, 5, 1)").contains("the bounds of a range must be finite"));
+}
+
+#[test]
+fn range_builtin_rejects_non_finite_steps() {
+    assert!(error("range(0, 1, inf * 0)").contains("the step of a range must be finite"));
+    assert!(error("range(0, 1, inf)").contains("the step of a range must be finite"));
+}
+
+#[test]
+fn loops_include_both_bounds() {
+    assert_eq!(display("x: collect [0..10, 3] => x"), "[0, 3, 6, 9, 10]");
+    assert_eq!(display("x: [0..10, 1] => x * 3"), "165");
+    assert_eq!(display("x: collect [3..3, 1] => x"), "[3]");
+}
+
+#[test]
+fn loops_reject_a_zero_step() {
+    assert!(error("x: [0..1, 0] => x").contains("a step cannot be 0"));
+}
+
+#[test]
+fn loops_reject_non_finite_bounds() {
+    assert!(error("x: [0..inf, 1] => x").contains("the bounds of a range must be finite"));
+    assert!(error("x: [inf * 0..5, 1] => x").contains("the bounds of a range must be finite"));
+}
+
+#[test]
+fn loops_reject_non_finite_steps() {
+    assert!(error("x: [0..1, inf * 0] => x").contains("the step of a range must be finite"));
+    assert!(error("x: [0..1, -inf] => x").contains("the step of a range must be finite"));
+}
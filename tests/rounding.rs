@@ -0,0 +1,37 @@
+mod common;
+
+use common::{display, real};
+
+#[test]
+fn round_to_rounds_to_decimal_places() {
+    assert_eq!(real("round_to(2.71828, 2)"), 2.72);
+    assert_eq!(real("round_to(2.5, 0)"), 3.0);
+    assert_eq!(real("round_to(-1.005, 1)"), -1.0);
+    assert_eq!(display("round_to(1.234 + 5.678i, 1)"), "1.2 + 5.7i");
+}
+
+#[test]
+fn round_to_leaves_numbers_alone_when_scaling_would_overflow() {
+    assert_eq!(real("round_to(1e10, 300)"), 1e10);
+    assert_eq!(real("round_to(1e300, 100)"), 1e300);
+    assert_eq!(real("round_to(1, 400)"), 1.0);
+}
+
+#[test]
+fn round_to_leaves_numbers_alone_past_the_precision_of_a_float() {
+    assert_eq!(real("round_to(0.1, 20)"), 0.1);
+    assert_eq!(real("round_to(1 / 3, 17)"), 1.0 / 3.0);
+    assert_eq!(real("round_to(123456789.123, 12)"), 123456789.123);
+}
+
+#[test]
+fn round_to_keeps_infinities_and_nan() {
+    assert_eq!(real("round_to(inf, 2)"), f64::INFINITY);
+    assert!(real("round_to(inf * 0, 2)").is_nan());
+}
+
+#[test]
+fn round_to_rejects_bad_digits() {
+    assert!(common::error("round_to(1, -1)").contains("expected a non-negative integer number of digits"));
+    assert!(common::error("round_to(1, 0.5)").contains("expected a non-negative integer number of digits"));
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::{error, real};
+
+#[test]
+fn a_loop_body_can_declare_a_variable_every_iteration() {
+    assert_eq!(real("let total = 0\nx: [1..3, 1] => {\n    let y = x * 2\n    total = total + y\n}\ntotal"), 12.0);
+}
+
+#[test]
+fn a_loop_body_can_declare_a_function_every_iteration() {
+    assert_eq!(real("let total = 0\nx: [1..3, 1] => {\n    let double(y) = y * 2\n    total = total + double(x)\n}\ntotal"), 12.0);
+}
+
+#[test]
+fn a_function_body_can_declare_a_function_every_call() {
+    assert_eq!(real("let f(n) = {\n    let g(y) = y + 1\n    g(n)\n}\nf(1) + f(2)"), 5.0);
+}
+
+#[test]
+fn functions_declared_in_a_tree_are_gone_after_it() {
+    assert!(error("let x = {\n    let g(y) = y\n    g(1)\n}\ng(2)").contains("unknown function: g"));
+}
+
+#[test]
+fn redeclaring_a_function_in_the_same_scope_is_an_error() {
+    assert!(error("let f(x) = x\nlet f(x) = x\n1").contains("redeclared a function that already is defined"));
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::{display, error, real};
+
+#[test]
+fn strings_are_compared_by_their_contents() {
+    assert_eq!(display("\"hello\""), "hello");
+    assert_eq!(display("\"hello\" == \"hello\""), "true");
+    assert_eq!(display("\"hello\" == \"world\""), "false");
+    assert_eq!(display("\"1\" == 1"), "false");
+}
+
+#[test]
+fn adding_a_string_joins_the_values() {
+    assert_eq!(display("\"x = \" + 5"), "x = 5");
+    assert_eq!(display("5 + \"a\""), "5a");
+    assert_eq!(display("\"a\" + \"b\" == \"ab\""), "true");
+}
+
+#[test]
+fn format_fills_in_placeholders() {
+    assert_eq!(display("format(\"{} + {}\", 1, 2)"), "1 + 2");
+    assert_eq!(display("format(\"{{}} {}\", 1)"), "{} 1");
+    assert!(error("format(\"{} {}\", 1)").starts_with("format string has 2 {} placeholders but 1 values were given"));
+    assert!(error("format(\"{}\", 1, 2)").starts_with("format string has 1 {} placeholders but 2 values were given"));
+}
+
+#[test]
+fn strings_have_a_length_and_characters() {
+    assert_eq!(real("len(\"hello\")"), 5.0);
+    assert_eq!(real("len([50, [20, 4], 0, 5.1])"), 4.0);
+    assert_eq!(display("chars(\"abc\")"), "[a, b, c]");
+    assert_eq!(display("chars(\"abc\")[1] == \"b\""), "true");
+    assert!(error("len(5)").starts_with("expected an array or string"));
+    assert!(error("chars(5)").starts_with("expected a string to split into characters"));
+}
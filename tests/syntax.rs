@@ -0,0 +1,25 @@
+mod common;
+
+use common::{display, real};
+
+#[test]
+fn hex_binary_and_scientific_literals() {
+    assert_eq!(real("0x1F"), 31.0);
+    assert_eq!(real("0b1010"), 10.0);
+    assert_eq!(real("1e3"), 1000.0);
+    assert_eq!(real("2.5E-2"), 0.025);
+    assert_eq!(display("3i + 0x10"), "16 + 3i");
+}
+
+#[test]
+fn underscores_separate_digits() {
+    assert_eq!(real("1_000_000"), 1_000_000.0);
+    assert_eq!(real("0xff_ff"), 65535.0);
+}
+
+#[test]
+fn line_comments_are_ignored() {
+    assert_eq!(real("1 // a comment\n+ 2"), 3.0);
+    assert_eq!(real("// only a comment\n5"), 5.0);
+    assert_eq!(real("let x = 2 // the first\nx // and the last"), 2.0);
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use common::{display, error, real};
+
+#[test]
+fn assignment_updates_an_existing_variable() {
+    assert_eq!(real("let total = 0\nx: [1..4, 1] => { total = total + x }\ntotal"), 10.0);
+    assert_eq!(real("let a = 1\nlet b = 0\na = b = 3\na + b"), 6.0);
+}
+
+#[test]
+fn variables_cannot_be_redeclared_or_assigned_before_being_declared() {
+    assert!(error("let total = 0\nlet total = 5\ntotal").starts_with("you cannot redeclare a variable: total"));
+    assert!(error("nothing = 5\n1").starts_with("unknown variable: nothing"));
+}
+
+#[test]
+fn destructuring_assignment_unpacks_arrays() {
+    assert_eq!(display("let q = 0\nlet r = 0\nq, r = divmod(7, 3)\n[q, r]"), "[2, 1]");
+    assert!(error("let q = 0\nlet r = 0\nq, r = [1, 2, 3]\nq").starts_with("cannot unpack an array of 3 elements into 2 variables"));
+    assert!(error("let q = 0\nlet r = 0\nq, r = 5\nq").starts_with("only an array can be unpacked into several variables"));
+}
+
+#[test]
+fn compound_assignment() {
+    assert_eq!(real("let product = 1\nx: [1..4, 1] => { product *= x }\nproduct"), 24.0);
+    assert_eq!(real("let a = 10\na -= 3\na /= 7\na += 1\na"), 2.0);
+    assert!(error("b += 1").starts_with("unknown variable: b"));
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::{display, error, real};
+use std::f64::consts::FRAC_PI_2;
+
+#[test]
+fn vectors_can_have_any_number_of_components() {
+    assert_eq!(display("vec(2, 5, 1) + vec(1, 1, 1)"), "(3, 6, 2)");
+    assert_eq!(real("z(vec(2, 5, 1))"), 1.0);
+    assert_eq!(real("x(vec(2, 5)) + y(vec(2, 5))"), 7.0);
+    assert_eq!(display("vec(1, 2) * 3"), "(3, 6)");
+    assert!(error("vec(1)").starts_with("a vector needs at least 2 components, got 1"));
+    assert!(error("z(vec(2, 5))").starts_with("cannot take the z component of a 2d vector"));
+    assert!(error("vec(1, 2) + vec(1, 2, 3)").starts_with("cannot add vectors of different dimensions 2 and 3"));
+}
+
+#[test]
+fn dot_and_cross_products() {
+    assert_eq!(real("dot(vec(1, 2), vec(3, 4))"), 11.0);
+    assert_eq!(display("cross(vec(1, 0, 0), vec(0, 1, 0))"), "(0, 0, 1)");
+    assert_eq!(real("cross(vec(1, 0), vec(0, 1))"), 1.0);
+    assert!(error("dot(vec(1, 2), vec(1, 2, 3))").starts_with("cannot find dot product of vectors of different dimensions"));
+    assert!(error("cross(vec(1, 0), vec(0, 1, 0))").starts_with("cannot find cross product of vectors with 2 and 3 components"));
+}
+
+#[test]
+fn magnitude_and_normalize() {
+    assert_eq!(real("magnitude(vec(3, 4))"), 5.0);
+    assert_eq!(display("normalize(vec(3, 4))"), "(0.6, 0.8)");
+    assert!(error("normalize(vec(0, 0))").starts_with("cannot normalize the zero vector"));
+}
+
+#[test]
+fn the_standard_library_length_works_in_any_dimension() {
+    assert_eq!(real("length(vec(3, 4))"), 5.0);
+    assert_eq!(real("length(vec(1, 2, 2))"), 3.0);
+}
+
+#[test]
+fn distance_and_angle() {
+    assert_eq!(real("distance(vec(0, 0), vec(3, 4))"), 5.0);
+    assert_eq!(real("angle(vec(1, 0), vec(0, 1))"), FRAC_PI_2);
+    assert!(error("angle(vec(0, 0), vec(0, 1))").starts_with("cannot find angle involving the zero vector"));
+}
+
+#[test]
+fn rotate_turns_2d_vectors_counterclockwise() {
+    assert!(real("x(rotate(vec(1, 0), pi / 2))").abs() < 1e-15);
+    assert_eq!(real("y(rotate(vec(1, 0), pi / 2))"), 1.0);
+    assert!(error("rotate(vec(1, 0, 0), 1)").starts_with("cannot rotate a vector with 3 components"));
+}
+
+#[test]
+fn matrices_are_shown_row_by_row() {
+    assert_eq!(display("matrix([[1, 2], [3, 4]])"), "[1, 2\n 3, 4]");
+    assert!(error("matrix([[1, 2], [3]])").starts_with("cannot create a matrix from rows of different lengths 2 and 1"));
+}
+
+#[test]
+fn matrix_arithmetic() {
+    assert_eq!(display("matrix([[1, 2], [3, 4]]) + matrix([[1, 1], [1, 1]])"), "[2, 3\n 4, 5]");
+    assert_eq!(display("matrix([[1, 2], [3, 4]]) * matrix([[0, 1], [1, 0]])"), "[2, 1\n 4, 3]");
+    assert_eq!(display("matrix([[1, 2], [3, 4]]) * 2"), "[2, 4\n 6, 8]");
+    assert!(error("matrix([[1, 2], [3, 4]]) * matrix([[1, 2, 3]])").contains("the inner dimensions differ"));
+    assert!(error("matrix([[1, 2]]) + matrix([[1], [2]])").starts_with("cannot add matrices of different shapes 1x2 and 2x1"));
+}
+
+#[test]
+fn transpose_determinant_and_inverse() {
+    assert_eq!(display("transpose(matrix([[1, 2, 3]]))"), "[1\n 2\n 3]");
+    assert_eq!(real("det(matrix([[1, 2], [3, 4]]))"), -2.0);
+    assert_eq!(display("inverse(matrix([[2, 0], [0, 4]]))"), "[0.5, 0\n 0, 0.25]");
+    assert!(error("det(matrix([[1, 2, 3]]))").contains("it is not square"));
+    assert!(error("inverse(matrix([[1, 2], [2, 4]]))").starts_with("cannot find inverse, matrix is singular"));
+}
+
+#[test]
+fn identity_and_zeros() {
+    assert_eq!(display("identity(2)"), "[1, 0\n 0, 1]");
+    assert_eq!(display("zeros(2, 3)"), "[0, 0, 0\n 0, 0, 0]");
+    assert_eq!(display("matrix([[1, 2], [3, 4]]) * identity(2)"), "[1, 2\n 3, 4]");
+    assert!(error("identity(0)").starts_with("expected a positive integer size"));
+    assert!(error("zeros(0, 1)").starts_with("expected a positive integer number of rows"));
+}